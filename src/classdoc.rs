@@ -1,19 +1,128 @@
-use std::str::FromStr;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
+use rust_i18n::t;
 use tracing::{debug, instrument, warn};
 
 use crate::{
-    javadoc::{node_to_docable, FileContext, JavaDocable, JavaDocableElement},
+    javadoc::{
+        inheritance::collect_hierarchy,
+        model, node_to_docable, prefix_hashes,
+        symbols::{collect_symbols, SymbolTable},
+        FileContext, JavaDocable, JavaDocableElement, Selection, TextFormat,
+    },
+    json::{render_file, SourceFile},
+    output::Format,
     parser::parse_string,
+    template::Templates,
 };
 
+/// The rendered documentation of a single source file
+#[derive(Debug)]
+pub struct ClassDoc {
+    /// Declared package of the file, if any
+    pub package: Option<String>,
+    /// Names of all documented top level types
+    pub types: Vec<String>,
+    /// Rendered asciidoc, markdown or JSON
+    pub content: String,
+    /// Model of the documented elements with `--format json`, to combine them into a single
+    /// document
+    pub json: Option<SourceFile>,
+}
+
+/// Collects all types of a file documented according to `selection`, whose documentation will be
+/// written to `page`, and the hierarchy of all its types with their documentation converted to
+/// `format`
 #[instrument(skip_all)]
-pub fn from_sourcecode(sourcecode: &str) -> anyhow::Result<String> {
+pub fn symbols_from_sourcecode(
+    sourcecode: &str,
+    page: &Path,
+    selection: Selection,
+    format: TextFormat,
+) -> anyhow::Result<SymbolTable> {
     let tree = parse_string(sourcecode)?;
-    debug!("Getting root node first");
     let root = tree.root_node();
     let mut cursor = root.walk();
+    let filecontext = FileContext::from_str(sourcecode)?
+        .with_selection(selection)
+        .with_text_format(format);
+
+    let children: Vec<JavaDocableElement> = root
+        .children(&mut cursor)
+        .filter_map(|node| node_to_docable(node, &filecontext))
+        .collect();
+    let mut symbols = SymbolTable::default();
+    for symbol in collect_symbols(&children, &filecontext, page) {
+        symbols.insert(symbol);
+    }
+    for (fqn, hierarchy) in collect_hierarchy(root, &filecontext) {
+        symbols.insert_hierarchy(fqn, hierarchy);
+    }
+    Ok(symbols)
+}
+
+/// Package declared by a file, if any
+pub fn declared_package(sourcecode: &str) -> anyhow::Result<Option<String>> {
     let filecontext = FileContext::from_str(sourcecode)?;
+    Ok(filecontext.package().map(|package| package.to_owned()))
+}
+
+/// How the documentation of a file is rendered
+#[derive(Debug, Clone, Default)]
+pub struct RenderSettings {
+    /// Path of the source file, recorded in the JSON export
+    pub source: PathBuf,
+    /// Directory `xref:` targets are relative to, instead of the page
+    pub xref_root: Option<PathBuf>,
+    /// Directories external snippets are looked up in
+    pub snippet_dirs: Vec<PathBuf>,
+    /// Which declarations are documented
+    pub selection: Selection,
+    /// Section level of types, packages and modules are rendered one level above
+    pub level: u8,
+    /// Wrap every type and member into a tagged region named after its anchor
+    pub tagged_regions: bool,
+    /// Format of the page
+    pub format: Format,
+    /// Templates to render the page with, instead of the default asciidoc layout, which the
+    /// built-in asciidoc templates reproduce. Markdown pages are always rendered with templates.
+    pub templates: Option<Arc<Templates>>,
+}
+
+/// Renders the documentation of a file to `page`, linking to other documented types in `symbols`
+#[instrument(skip_all)]
+pub fn from_sourcecode(
+    sourcecode: &str,
+    symbols: Arc<SymbolTable>,
+    page: &Path,
+    settings: RenderSettings,
+) -> anyhow::Result<ClassDoc> {
+    let RenderSettings {
+        source,
+        xref_root,
+        snippet_dirs,
+        selection,
+        level,
+        tagged_regions,
+        format,
+        templates,
+    } = settings;
+    let tree = parse_string(sourcecode)?;
+    debug!("Getting root node first");
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let filecontext = FileContext::from_str(sourcecode)?
+        .with_links(symbols, page.to_owned(), xref_root)
+        .with_snippet_dirs(snippet_dirs)
+        .with_selection(selection)
+        .with_tagged_regions(tagged_regions)
+        .with_text_format(format.text_format());
+
+    let package = filecontext.package().map(|package| package.to_owned());
 
     let children: Vec<JavaDocableElement> = root
         .children(&mut cursor)
@@ -21,17 +130,435 @@ pub fn from_sourcecode(sourcecode: &str) -> anyhow::Result<String> {
         .collect();
 
     debug!("{children:?}");
+    let types: Vec<String> = children
+        .iter()
+        .filter_map(|child| match child {
+            JavaDocableElement::Class(child) => Some(child.get_name()),
+            JavaDocableElement::Interface(child) => Some(child.get_name()),
+            JavaDocableElement::Enum(child) => Some(child.get_name()),
+            JavaDocableElement::Record(child) => Some(child.get_name()),
+            JavaDocableElement::AnnotationType(child) => Some(child.get_name()),
+            _ => None,
+        })
+        .collect();
+    let (content, json) = match (format, templates) {
+        (Format::Json, _) if !children.is_empty() => {
+            let file = SourceFile::new(&source, model::page(&children, &filecontext));
+            (render_file(&file)?, Some(file))
+        }
+        (_, Some(templates)) if !children.is_empty() => {
+            let page = model::page(&children, &filecontext);
+            (templates.render_page(&page, level, tagged_regions)?, None)
+        }
+        _ => (render_children(&children, level), None),
+    };
+
+    Ok(ClassDoc {
+        package,
+        types,
+        content,
+        json,
+    })
+}
+
+/// Renders documented elements with the default asciidoc layout, types at `level` and packages and modules
+/// one level above
+fn render_children<'a>(children: &'a [JavaDocableElement<'a>], level: u8) -> String {
+    let outer_level = level.saturating_sub(1);
     let result: Vec<String> = children
-        .into_iter()
+        .iter()
         .map(|child| match child {
-            JavaDocableElement::Class(child) => child.render(0),
-            JavaDocableElement::Field(child) => child.render(0),
-            JavaDocableElement::Method(child) => child.render(0),
-            JavaDocableElement::Constructor(child) => child.render(0),
-            JavaDocableElement::Interface(child) => child.render(0),
+            JavaDocableElement::Class(child) => child.render(level),
+            JavaDocableElement::Field(child) => child.render(level),
+            JavaDocableElement::Method(child) => child.render(level),
+            JavaDocableElement::Constructor(child) => child.render(level),
+            JavaDocableElement::Interface(child) => child.render(level),
+            JavaDocableElement::Enum(child) => child.render(level),
+            JavaDocableElement::EnumConstant(child) => child.render(level),
+            JavaDocableElement::Record(child) => child.render(level),
+            JavaDocableElement::AnnotationType(child) => child.render(level),
+            JavaDocableElement::AnnotationElement(child) => child.render(level),
+            JavaDocableElement::Package(child) => child.render(outer_level),
+            JavaDocableElement::Module(child) => child.render(outer_level),
+        })
+        .collect();
+    result.join("")
+}
+
+/// Renders the list of types belonging to a package, as `(name, path)` pairs of type name and
+/// path of the generated page relative to the package overview page
+pub fn render_package_types(types: &[(String, String)], format: TextFormat) -> String {
+    let prefix_hashes = prefix_hashes(0);
+    let headline = t!("package_types_headline", nesting = prefix_hashes);
+    let headline = match format {
+        TextFormat::Asciidoc => headline.to_string(),
+        TextFormat::Markdown => markdown_headline(&headline),
+    };
+    let types: Vec<String> = types
+        .iter()
+        .map(|(name, path)| match format {
+            TextFormat::Asciidoc => format!("* xref:{path}[{name}]"),
+            TextFormat::Markdown => format!("- [{name}]({path})"),
         })
         .collect();
-    let result = result.join("");
+    let types = types.join("\n");
+    format!("\n{headline}\n\n{types}\n")
+}
+
+/// Combines the documentation of all files, rendered with types on level 2, into a single
+/// document ordered by package and type. Packages without overview get a headline of their own.
+pub fn render_single_document(docs: &[&ClassDoc], format: TextFormat) -> String {
+    let mut docs: Vec<&ClassDoc> = docs
+        .iter()
+        .copied()
+        .filter(|classdoc| !classdoc.content.is_empty())
+        .collect();
+    // package overviews and modules have no types, so they sort in front of the types
+    docs.sort_by(|a, b| (&a.package, a.types.first()).cmp(&(&b.package, b.types.first())));
+
+    let title = t!("index_title");
+    let mut content = match format {
+        TextFormat::Asciidoc => format!("= {title}\n:toc: left\n:toclevels: 2\n:sectanchors:\n"),
+        TextFormat::Markdown => format!("# {title}\n"),
+    };
+    let mut current: Option<&Option<String>> = None;
+    for classdoc in docs {
+        let is_module = classdoc.types.is_empty() && classdoc.package.is_none();
+        if !is_module {
+            if classdoc.types.is_empty() {
+                // a package overview, which comes with its own headline
+                current = Some(&classdoc.package);
+            } else if current != Some(&classdoc.package) {
+                let prefix_hashes = prefix_hashes(1);
+                let name = match &classdoc.package {
+                    Some(package) => package.clone(),
+                    None => t!("default_package").to_string(),
+                };
+                let headline = t!("package_headline", nesting = prefix_hashes, name = name);
+                let headline = match format {
+                    TextFormat::Asciidoc => headline.to_string(),
+                    TextFormat::Markdown => markdown_headline(&headline),
+                };
+                content.push_str(&format!("\n\n{headline}\n"));
+                current = Some(&classdoc.package);
+            }
+        }
+        content.push_str(&classdoc.content);
+    }
+    content.push('\n');
+    content
+}
+
+/// Turns the section markers of an asciidoc headline into the ones of markdown
+fn markdown_headline(headline: &str) -> String {
+    let title = headline.trim_start_matches('=');
+    let level = headline.len() - title.len();
+    format!("{} {}", "#".repeat(level), title.trim_start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classdoc(package: Option<&str>, types: &[&str], content: &str) -> ClassDoc {
+        ClassDoc {
+            package: package.map(|package| package.to_owned()),
+            types: types.iter().map(|name| name.to_string()).collect(),
+            content: content.to_owned(),
+            json: None,
+        }
+    }
+
+    #[test]
+    fn renders_single_document() {
+        let foo = classdoc(Some("com.acme"), &["Foo"], "\n\n=== Foo\n");
+        let bar = classdoc(Some("com.acme"), &["Bar"], "\n\n=== Bar\n");
+        let overview = classdoc(Some("com.acme"), &[], "\n\n== Package com.acme\n");
+        let util = classdoc(Some("com.acme.util"), &["Strings"], "\n\n=== Strings\n");
+        let empty = classdoc(Some("com.acme"), &[], "");
+
+        let rendered = render_single_document(
+            &[&util, &foo, &empty, &overview, &bar],
+            TextFormat::Asciidoc,
+        );
+
+        assert_eq!(
+            rendered,
+            "= API Reference
+:toc: left
+:toclevels: 2
+:sectanchors:
+
+
+== Package com.acme
+
+
+=== Bar
+
+
+=== Foo
+
+
+== Package com.acme.util
+
+
+=== Strings
+
+"
+        );
+    }
+
+    #[test]
+    fn inherits_documentation_in_the_output_format() {
+        let base = "
+package com.acme;
+
+public class Base {
+    /**
+     * See <a href=\"https://x.org\">docs</a>.
+     * @param x the <i>x</i>
+     */
+    public void run(int x) {}
+}";
+        let implementation = "
+package com.acme;
+
+public class Impl extends Base {
+    @Override
+    public void run(int x) {}
+}";
+        let settings = RenderSettings {
+            source: PathBuf::from("Impl.java"),
+            selection: Selection {
+                include_undocumented: true,
+                ..Selection::default()
+            },
+            format: Format::Json,
+            ..RenderSettings::default()
+        };
+        let format = settings.format.text_format();
+        let mut symbols = SymbolTable::default();
+        for (source, page) in [(base, "out/Base.json"), (implementation, "out/Impl.json")] {
+            let page = Path::new(page);
+            symbols
+                .merge(symbols_from_sourcecode(source, page, settings.selection, format).unwrap());
+        }
+        let classdoc = from_sourcecode(
+            implementation,
+            Arc::new(symbols),
+            Path::new("out/Impl.json"),
+            settings,
+        )
+        .unwrap();
+        let method = &classdoc.json.unwrap().page.elements[0].children[0];
+        assert_eq!(method.description, "See [docs](https://x.org).");
+        assert_eq!(method.parameters[0].description, "the _x_");
+    }
+
+    #[test]
+    fn resolves_interface_constants() {
+        let sourcecode = "
+package com.acme;
+
+/** Limits, at most {@value #MAX}. */
+public interface Limits {
+    /** The maximum */
+    int MAX = 42;
+
+    /** Checks against {@value Limits#MAX}. */
+    boolean check(int value);
+}";
+        let settings = RenderSettings {
+            source: PathBuf::from("Limits.java"),
+            ..RenderSettings::default()
+        };
+        let page = Path::new("out/Limits.adoc");
+        let symbols = symbols_from_sourcecode(
+            sourcecode,
+            page,
+            settings.selection,
+            settings.format.text_format(),
+        )
+        .unwrap();
+        let classdoc = from_sourcecode(sourcecode, Arc::new(symbols), page, settings).unwrap();
+        assert!(classdoc.content.contains("Limits, at most `+42+`."));
+        assert!(classdoc.content.contains("Checks against `+42+`."));
+        assert!(classdoc.content.contains("[[com.acme.Limits-MAX]]"));
+        assert!(classdoc.content.contains("The maximum"));
+    }
+
+    #[test]
+    fn limits_section_levels() {
+        let sourcecode = "
+package com.acme;
+
+/** A. */
+public class A {
+    /** B. */
+    public static class B {
+        /** C. */
+        public static class C {
+            /** Runs. */
+            public void run() {}
+        }
+    }
+}";
+        let settings = RenderSettings {
+            source: PathBuf::from("A.java"),
+            level: 2,
+            ..RenderSettings::default()
+        };
+        let page = Path::new("out/api.adoc");
+        let classdoc =
+            from_sourcecode(sourcecode, Arc::new(SymbolTable::default()), page, settings).unwrap();
+        assert!(classdoc.content.contains("\n===== C\n"));
+        assert!(classdoc.content.contains("\n====== run()\n"));
+        assert!(!classdoc.content.contains("======="));
+    }
+
+    const LAYOUT_SOURCES: &[&str] = &[
+        "
+package com.acme;
+
+import java.io.IOException;
+
+/**
+ * A <b>box</b> of {@link Item items}.
+ * @param <T> the content
+ * @since 1.0
+ */
+public class Box<T extends Item> implements Cloneable {
+    /** The size. */
+    public static final int SIZE = 3;
+
+    /** Creates it. */
+    public Box() {}
+
+    /**
+     * Opens the box.
+     * @param force whether to force it
+     * @param <U> the result | type
+     * @return the result
+     * @throws IOException when stuck
+     */
+    public <U> U open(boolean force, T... items) throws IOException { return null; }
+
+    /** Closes it. */
+    public void close() {}
+
+    /** Kinds of boxes. */
+    public enum Kind {
+        /** Small */
+        SMALL,
+        /** Large */
+        LARGE;
+
+        /** Whether it is big. */
+        public boolean big() { return this == LARGE; }
+    }
+
+    /** A listener. */
+    public interface Listener {
+        /** A constant. */
+        int MAX = 2;
+
+        /** Notifies. */
+        void notify(Box<?> box);
+
+        /** Nested in the nested interface. */
+        record Event(int id) {}
+    }
+
+    /**
+     * A point.
+     * @param x the x
+     * @param y the y
+     */
+    public record Point(int x, int y) {
+        /** Validates. */
+        public Point {}
+
+        /** The origin. */
+        public static Point origin() { return new Point(0, 0); }
+    }
+
+    /** Marks boxes. */
+    @Retention(RetentionPolicy.RUNTIME)
+    @Target({ElementType.TYPE})
+    public @interface Marked {
+        /** The name. */
+        String name() default \"\";
+
+        /** Priority. */
+        int priority();
+    }
+
+    /** Deeper. */
+    public static class Inner {
+        /** Deepest. */
+        public static class Innermost {
+            /** Runs. */
+            public void run() {}
+        }
+    }
+}
+
+/** Undocumented members. */
+interface Plain {
+    void nothing();
+}",
+        "
+/**
+ * The acme package.
+ * @since 2.0
+ */
+package com.acme;",
+        "
+/**
+ * The acme module.
+ * @since 3.0
+ */
+open module com.acme {
+    requires transitive java.base;
+    requires static java.compiler;
+    exports com.acme;
+    exports com.acme.internal to com.other, com.third;
+    opens com.acme.data;
+    uses com.acme.spi.Service;
+    provides com.acme.spi.Service with com.acme.Impl, com.acme.Other;
+}",
+    ];
 
-    Ok(result)
+    #[test]
+    fn renders_built_in_templates_like_the_default_layout() {
+        let templates =
+            Arc::new(smol::block_on(Templates::load(TextFormat::Asciidoc, None)).unwrap());
+        for sourcecode in LAYOUT_SOURCES {
+            for (level, tagged_regions) in [(0, false), (2, true)] {
+                let render = |templates: Option<Arc<Templates>>| {
+                    let settings = RenderSettings {
+                        source: PathBuf::from("Box.java"),
+                        level,
+                        tagged_regions,
+                        templates,
+                        ..RenderSettings::default()
+                    };
+                    let page = Path::new("out/Box.adoc");
+                    let symbols = symbols_from_sourcecode(
+                        sourcecode,
+                        page,
+                        settings.selection,
+                        TextFormat::Asciidoc,
+                    )
+                    .unwrap();
+                    from_sourcecode(sourcecode, Arc::new(symbols), page, settings)
+                        .unwrap()
+                        .content
+                };
+                let default = render(None);
+                let templated = render(Some(templates.clone()));
+                assert_eq!(templated, default);
+            }
+        }
+    }
 }
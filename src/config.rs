@@ -2,6 +2,11 @@ use std::fmt::Display;
 
 use clap::{Parser, ValueEnum};
 
+use crate::{
+    javadoc::visibility::Visibility,
+    output::{Format, Layout},
+};
+
 #[derive(Debug, Clone, ValueEnum)]
 pub enum Locale {
     En,
@@ -31,4 +36,52 @@ pub struct Config {
     /// Localization to use for file generation
     #[arg(short, long, default_value_t = Locale::En)]
     pub locale: Locale,
+
+    /// Directory with external `{@snippet}` files, searched after the `snippet-files` directory
+    /// next to the source file. Can be given multiple times.
+    #[arg(long)]
+    pub snippet_path: Vec<String>,
+
+    /// Also document types and members without documentation comment, showing their signature
+    /// and a "No description" placeholder
+    #[arg(long)]
+    pub include_undocumented: bool,
+
+    /// Minimum access level of documented types and members, like the `-public`, `-protected`,
+    /// `-package` and `-private` options of javadoc
+    #[arg(long, default_value_t = Visibility::Private)]
+    pub visibility: Visibility,
+
+    /// Format of the generated pages, Markdown pages end in `.md` instead of `.adoc`
+    #[arg(long, default_value_t = Format::Asciidoc)]
+    pub format: Format,
+
+    /// Layout of the generated pages: mirroring the paths of the source files, one directory per
+    /// declared package, or the pages of an Antora module with one directory per package
+    #[arg(long, default_value_t = Layout::Source)]
+    pub layout: Layout,
+
+    /// Prefix removed from the paths of the source files with `--layout source`, like
+    /// `src/main/java`. Can be given multiple times, the first matching one is removed.
+    #[arg(long)]
+    pub source_root: Vec<String>,
+
+    /// Name of the Antora module to generate with `--layout antora`
+    #[arg(long, default_value = "ROOT")]
+    pub antora_module: String,
+
+    /// Combine the documentation of all files into this single document in the output directory,
+    /// like for PDF builds, instead of writing one page per file
+    #[arg(long)]
+    pub single_file: Option<String>,
+
+    /// Wrap every type and member into a tagged region named after its anchor, to include it
+    /// elsewhere with `include::Foo.adoc[tag=com.acme.Foo-bar-int-]`
+    #[arg(long)]
+    pub tagged_regions: bool,
+
+    /// Directory with templates overriding the built-in `page.jinja`, `type.jinja` and
+    /// `member.jinja` templates of the format the pages are rendered with
+    #[arg(long)]
+    pub templates: Option<String>,
 }
@@ -0,0 +1,185 @@
+//! Entry points to the generated pages: an overview of all documented packages and types, and an
+//! Antora style navigation file mirroring the package hierarchy.
+
+use std::collections::BTreeMap;
+
+use rust_i18n::t;
+
+use crate::javadoc::TextFormat;
+
+/// The documented types of a package, with all paths relative to the output directory
+#[derive(Debug, Default)]
+pub struct PackageEntry {
+    /// Overview page generated from the `package-info.java` of the package, if any
+    pub overview: Option<String>,
+    /// Names of all documented top level types and their pages
+    pub types: Vec<(String, String)>,
+}
+
+/// All documented packages, `None` being the default package
+pub type PackageIndex = BTreeMap<Option<String>, PackageEntry>;
+
+fn package_label(package: &Option<String>) -> String {
+    match package {
+        Some(package) => package.clone(),
+        None => t!("default_package").to_string(),
+    }
+}
+
+fn sorted_types(entry: &PackageEntry) -> Vec<&(String, String)> {
+    let mut types: Vec<&(String, String)> = entry.types.iter().collect();
+    types.sort();
+    types
+}
+
+/// Renders the top level overview page listing all packages and their types
+pub fn render_index(packages: &PackageIndex, format: TextFormat) -> String {
+    let title = t!("index_title");
+    let item = |depth: usize, label: &str, path: Option<&str>| match (format, path) {
+        (TextFormat::Asciidoc, Some(path)) => format!("{} xref:{path}[{label}]", "*".repeat(depth)),
+        (TextFormat::Asciidoc, None) => format!("{} {label}", "*".repeat(depth)),
+        (TextFormat::Markdown, Some(path)) => {
+            format!("{}- [{label}]({path})", "  ".repeat(depth - 1))
+        }
+        (TextFormat::Markdown, None) => format!("{}- {label}", "  ".repeat(depth - 1)),
+    };
+    let mut lines: Vec<String> = vec![];
+    for (package, entry) in packages {
+        let label = package_label(package);
+        lines.push(item(1, &label, entry.overview.as_deref()));
+        for (name, path) in sorted_types(entry) {
+            lines.push(item(2, name, Some(path)));
+        }
+    }
+    let lines = lines.join("\n");
+    match format {
+        TextFormat::Asciidoc => format!("= {title}\n\n{lines}\n"),
+        TextFormat::Markdown => format!("# {title}\n\n{lines}\n"),
+    }
+}
+
+/// Renders a navigation file with one nested entry per package name segment, listing the types of
+/// each package below it
+pub fn render_nav(packages: &PackageIndex, index: &str) -> String {
+    let title = t!("index_title");
+    let mut packages: Vec<(Vec<String>, &Option<String>, &PackageEntry)> = packages
+        .iter()
+        .map(|(package, entry)| {
+            let segments = match package {
+                Some(package) => package.split('.').map(|s| s.to_owned()).collect(),
+                None => vec![],
+            };
+            (segments, package, entry)
+        })
+        .collect();
+    packages.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut lines: Vec<String> = vec![format!(".xref:{index}[{title}]")];
+    let mut previous: Vec<String> = vec![];
+    for (segments, package, entry) in packages {
+        let common = previous
+            .iter()
+            .zip(segments.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        for (depth, segment) in segments.iter().enumerate().skip(common) {
+            let bullets = "*".repeat(depth + 1);
+            let is_package = depth + 1 == segments.len();
+            match &entry.overview {
+                Some(overview) if is_package => {
+                    lines.push(format!("{bullets} xref:{overview}[{segment}]"))
+                }
+                _ => lines.push(format!("{bullets} {segment}")),
+            }
+        }
+        if segments.is_empty() {
+            let label = package_label(package);
+            match &entry.overview {
+                Some(overview) => lines.push(format!("* xref:{overview}[{label}]")),
+                None => lines.push(format!("* {label}")),
+            }
+        }
+        let bullets = "*".repeat(segments.len().max(1) + 1);
+        for (name, path) in sorted_types(entry) {
+            lines.push(format!("{bullets} xref:{path}[{name}]"));
+        }
+        previous = segments;
+    }
+    let lines = lines.join("\n");
+    format!("{lines}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packages() -> PackageIndex {
+        let mut packages = PackageIndex::new();
+        packages.insert(
+            Some("com.acme".to_owned()),
+            PackageEntry {
+                overview: Some("com/acme/index.adoc".to_owned()),
+                types: vec![
+                    ("Foo".to_owned(), "com/acme/Foo.adoc".to_owned()),
+                    ("Bar".to_owned(), "com/acme/Bar.adoc".to_owned()),
+                ],
+            },
+        );
+        packages.insert(
+            Some("com.acme.util".to_owned()),
+            PackageEntry {
+                overview: None,
+                types: vec![(
+                    "Strings".to_owned(),
+                    "com/acme/util/Strings.adoc".to_owned(),
+                )],
+            },
+        );
+        packages
+    }
+
+    #[test]
+    fn renders_index() {
+        assert_eq!(
+            render_index(&packages(), TextFormat::Asciidoc),
+            "= API Reference
+
+* xref:com/acme/index.adoc[com.acme]
+** xref:com/acme/Bar.adoc[Bar]
+** xref:com/acme/Foo.adoc[Foo]
+* com.acme.util
+** xref:com/acme/util/Strings.adoc[Strings]
+"
+        );
+    }
+
+    #[test]
+    fn renders_markdown_index() {
+        assert_eq!(
+            render_index(&packages(), TextFormat::Markdown),
+            "# API Reference
+
+- [com.acme](com/acme/index.adoc)
+  - [Bar](com/acme/Bar.adoc)
+  - [Foo](com/acme/Foo.adoc)
+- com.acme.util
+  - [Strings](com/acme/util/Strings.adoc)
+"
+        );
+    }
+
+    #[test]
+    fn renders_nav() {
+        assert_eq!(
+            render_nav(&packages(), "index.adoc"),
+            ".xref:index.adoc[API Reference]
+* com
+** xref:com/acme/index.adoc[acme]
+*** xref:com/acme/Bar.adoc[Bar]
+*** xref:com/acme/Foo.adoc[Foo]
+*** util
+**** xref:com/acme/util/Strings.adoc[Strings]
+"
+        );
+    }
+}
@@ -0,0 +1,352 @@
+//! Anchor IDs for the generated pages, following the member anchors of the classic javadoc HTML
+//! output (`pkg.Type-method-int-java.lang.String-`), as those only use characters allowed in
+//! asciidoc IDs.
+
+use tree_sitter::Node;
+
+use super::{local_type_name, signature::parameters, FileContext};
+
+/// Anchor of a type, which is its fully qualified name
+pub fn type_anchor(fqn: &str) -> String {
+    fqn.to_owned()
+}
+
+/// Anchor of a field, enum constant or annotation element
+pub fn field_anchor(type_fqn: &str, name: &str) -> String {
+    format!("{type_fqn}-{name}")
+}
+
+/// Anchor of a method or constructor, given its already erased parameter types
+pub fn method_anchor(type_fqn: &str, name: &str, paramtypes: &[String]) -> String {
+    let paramtypes = paramtypes.join("-");
+    format!("{type_fqn}-{name}-{paramtypes}-")
+}
+
+/// Erases a declared parameter type to the form used in anchors: type arguments and annotations
+/// are removed, the base type is qualified by `qualify` and array dimensions become `:A`
+pub fn erase_type(paramtype: &str, qualify: impl Fn(&str) -> String) -> String {
+    let mut erased = String::new();
+    let mut depth: usize = 0;
+    for c in paramtype.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => erased.push(c),
+            _ => (),
+        }
+    }
+    let erased: Vec<&str> = erased
+        .split_whitespace()
+        .filter(|word| !word.starts_with('@'))
+        .collect();
+    let erased = erased.join("");
+
+    let mut base = erased.as_str();
+    let mut dimensions: usize = 0;
+    loop {
+        if let Some(rest) = base.strip_suffix("[]") {
+            base = rest;
+        } else if let Some(rest) = base.strip_suffix("...") {
+            base = rest;
+        } else {
+            break;
+        }
+        dimensions += 1;
+    }
+    let arrays = ":A".repeat(dimensions);
+    format!("{}{arrays}", qualify(base))
+}
+
+/// Erased types of the parameters of a method or constructor, as used in anchors. Compact
+/// constructors take the components of their record.
+pub fn erased_parameters(node: Node<'_>, ctx: &FileContext) -> Vec<String> {
+    let local_type = local_type_name(node, ctx);
+    let declaration = match node.grammar_name() {
+        "compact_constructor_declaration" => node.parent().and_then(|body| body.parent()),
+        _ => Some(node),
+    };
+    let Some(declaration) = declaration else {
+        return vec![];
+    };
+    let variables = type_variables(declaration, ctx);
+    parameters(declaration, ctx)
+        .iter()
+        .map(|param| {
+            erase_type(&param.paramtype, |t| {
+                qualify_erased(t, &variables, local_type.as_deref(), ctx)
+            })
+        })
+        .collect()
+}
+
+/// Type variables declared by `node` and its enclosing declarations with their first bound,
+/// innermost first as those shadow the outer ones
+fn type_variables<'a>(node: Node<'_>, ctx: &'a FileContext) -> Vec<(&'a str, Option<&'a str>)> {
+    let mut variables = vec![];
+    let mut current = Some(node);
+    while let Some(node) = current {
+        if let Some(params) = node.child_by_field_name("type_parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                let mut cursor = param.walk();
+                let children: Vec<Node> = param.named_children(&mut cursor).collect();
+                let Some(name) = children
+                    .iter()
+                    .find(|child| matches!(child.kind(), "identifier" | "type_identifier"))
+                else {
+                    continue;
+                };
+                let bound = children
+                    .iter()
+                    .find(|child| child.grammar_name() == "type_bound")
+                    .and_then(|bound| bound.named_child(0))
+                    .map(|bound| ctx.source_for_range(&bound.range()));
+                variables.push((ctx.source_for_range(&name.range()), bound));
+            }
+        }
+        current = node.parent();
+    }
+    variables
+}
+
+/// Qualifies an erased type name, erasing type variables to their first bound like javadoc does
+fn qualify_erased(
+    name: &str,
+    variables: &[(&str, Option<&str>)],
+    local_type: Option<&str>,
+    ctx: &FileContext,
+) -> String {
+    let Some(position) = variables.iter().position(|(variable, _)| *variable == name) else {
+        return ctx.qualify_type(name, local_type);
+    };
+    match variables[position].1 {
+        Some(bound) => {
+            // bounds may refer to other type variables, but never to the bound one again
+            let mut others = variables.to_vec();
+            others.remove(position);
+            erase_type(bound, |t| qualify_erased(t, &others, local_type, ctx))
+        }
+        None => "java.lang.Object".to_owned(),
+    }
+}
+
+/// Anchor of a declaration node, or `None` for nodes which are neither types nor members
+pub fn node_anchor(node: Node<'_>, ctx: &FileContext) -> Option<String> {
+    let local_type = local_type_name(node, ctx)?;
+    let type_fqn = ctx.fqn(&local_type);
+    let name = |node: Node<'_>| {
+        let name = node.child_by_field_name("name")?;
+        Some(ctx.source_for_range(&name.range()).to_owned())
+    };
+    match node.grammar_name() {
+        "class_declaration"
+        | "interface_declaration"
+        | "enum_declaration"
+        | "record_declaration"
+        | "annotation_type_declaration" => Some(type_anchor(&type_fqn)),
+        "method_declaration" | "constructor_declaration" | "compact_constructor_declaration" => {
+            let paramtypes = erased_parameters(node, ctx);
+            Some(method_anchor(&type_fqn, &name(node)?, &paramtypes))
+        }
+        "field_declaration" | "constant_declaration" => {
+            let declarator = node.child_by_field_name("declarator")?;
+            Some(field_anchor(&type_fqn, &name(declarator)?))
+        }
+        "enum_constant" => Some(field_anchor(&type_fqn, &name(node)?)),
+        "annotation_type_element_declaration" => Some(method_anchor(&type_fqn, &name(node)?, &[])),
+        _ => None,
+    }
+}
+
+/// Explicit anchor in front of the headline of a declaration, so links to it don't depend on the
+/// ids asciidoctor derives from the headline text
+pub fn render_anchor(node: Node<'_>, ctx: &FileContext) -> String {
+    match node_anchor(node, ctx) {
+        Some(anchor) => format!("[[{anchor}]]\n"),
+        None => String::new(),
+    }
+}
+
+/// Wraps the rendered documentation of a declaration into a region tagged with its anchor, if
+/// enabled, so it can be included on its own with `include::page.adoc[tag=anchor]`
+pub fn tagged_region(node: Node<'_>, ctx: &FileContext, rendered: String) -> String {
+    if !ctx.tagged_regions() {
+        return rendered;
+    }
+    match node_anchor(node, ctx) {
+        Some(anchor) => format!("\n\n// tag::{anchor}[]{rendered}\n// end::{anchor}[]\n"),
+        None => rendered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        javadoc::{node_to_docable, JavaDocable, JavaDocableElement},
+        parser::parse_string,
+    };
+
+    use super::*;
+
+    fn qualify(name: &str) -> String {
+        match name {
+            "String" => "java.lang.String".to_owned(),
+            "List" => "java.util.List".to_owned(),
+            _ => name.to_owned(),
+        }
+    }
+
+    #[test]
+    fn erases_types() {
+        assert_eq!(erase_type("int", qualify), "int");
+        assert_eq!(erase_type("String[]", qualify), "java.lang.String:A");
+        assert_eq!(erase_type("String...", qualify), "java.lang.String:A");
+        assert_eq!(erase_type("List<Map<K, V>>", qualify), "java.util.List");
+        assert_eq!(
+            erase_type("@NonNull String [] []", qualify),
+            "java.lang.String:A:A"
+        );
+    }
+
+    #[test]
+    fn builds_anchors() {
+        assert_eq!(type_anchor("com.acme.Foo"), "com.acme.Foo");
+        assert_eq!(field_anchor("com.acme.Foo", "bar"), "com.acme.Foo-bar");
+        assert_eq!(
+            method_anchor("com.acme.Foo", "bar", &[]),
+            "com.acme.Foo-bar--"
+        );
+        assert_eq!(
+            method_anchor(
+                "com.acme.Foo",
+                "bar",
+                &["int".to_owned(), "java.lang.String".to_owned()]
+            ),
+            "com.acme.Foo-bar-int-java.lang.String-"
+        );
+    }
+
+    #[test]
+    fn renders_anchors() {
+        let sourcecode = "
+package com.acme;
+
+/** Foo */
+public class Foo {
+    /** one */
+    public void bar(int a) {}
+
+    /** two */
+    public void bar(String[] a) {}
+}";
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let ctx = FileContext::from_str(sourcecode).unwrap();
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &ctx))
+            .collect();
+        let Some(JavaDocableElement::Class(class)) = children.last() else {
+            panic!("Got something else than a class????");
+        };
+        let rendered = class.render(0);
+        assert!(rendered.contains("[[com.acme.Foo]]\n= Foo"));
+        assert!(rendered.contains("[[com.acme.Foo-bar-int-]]\n=== bar(int)"));
+        assert!(rendered.contains("[[com.acme.Foo-bar-java.lang.String:A-]]\n=== bar(String[])"));
+    }
+
+    #[test]
+    fn qualifies_java_lang_types() {
+        let sourcecode = "
+package com.acme;
+
+class F {
+    void fail(IllegalArgumentException e) {}
+
+    void close(AutoCloseable c) {}
+
+    void other(Helper h) {}
+}";
+        let tree = parse_string(sourcecode).unwrap();
+        let ctx = FileContext::from_str(sourcecode).unwrap();
+        let class = tree.root_node().named_child(1).unwrap();
+        let body = class.child_by_field_name("body").unwrap();
+        let mut cursor = body.walk();
+        let anchors: Vec<String> = body
+            .named_children(&mut cursor)
+            .filter_map(|method| node_anchor(method, &ctx))
+            .collect();
+        assert_eq!(
+            anchors,
+            vec![
+                "com.acme.F-fail-java.lang.IllegalArgumentException-",
+                "com.acme.F-close-java.lang.AutoCloseable-",
+                "com.acme.F-other-com.acme.Helper-",
+            ]
+        );
+    }
+
+    #[test]
+    fn erases_type_variables() {
+        let sourcecode = "
+package p;
+
+class Tricky<E extends Number> {
+    <U> U id(U u) {}
+    <T extends Comparable<T>> void sort(T[] values, E other) {}
+    <K, V extends K> void put(V value) {}
+    <E> void shadow(E e) {}
+}";
+        let tree = parse_string(sourcecode).unwrap();
+        let ctx = FileContext::from_str(sourcecode).unwrap();
+        let class = tree.root_node().named_child(1).unwrap();
+        let body = class.child_by_field_name("body").unwrap();
+        let mut cursor = body.walk();
+        let anchors: Vec<String> = body
+            .named_children(&mut cursor)
+            .filter_map(|method| node_anchor(method, &ctx))
+            .collect();
+        assert_eq!(
+            anchors,
+            vec![
+                "p.Tricky-id-java.lang.Object-",
+                "p.Tricky-sort-java.lang.Comparable:A-java.lang.Number-",
+                "p.Tricky-put-java.lang.Object-",
+                "p.Tricky-shadow-java.lang.Object-",
+            ]
+        );
+    }
+
+    #[test]
+    fn tags_regions() {
+        let sourcecode = "
+package com.acme;
+
+/** Foo */
+public class Foo {
+    /** one */
+    public void bar(int a) {}
+}";
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let ctx = FileContext::from_str(sourcecode)
+            .unwrap()
+            .with_tagged_regions(true);
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &ctx))
+            .collect();
+        let Some(JavaDocableElement::Class(class)) = children.last() else {
+            panic!("Got something else than a class????");
+        };
+        let rendered = class.render(0);
+        assert!(rendered.starts_with("\n\n// tag::com.acme.Foo[]\n\n[[com.acme.Foo]]\n= Foo"));
+        assert!(rendered.ends_with("// end::com.acme.Foo[]\n"));
+        assert!(rendered.contains("// tag::com.acme.Foo-bar-int-[]\n\n[[com.acme.Foo-bar-int-]]"));
+        assert!(rendered.contains("// end::com.acme.Foo-bar-int-[]\n"));
+    }
+}
@@ -0,0 +1,84 @@
+use rust_i18n::t;
+use tracing::debug;
+use tree_sitter::Node;
+
+use super::{
+    anchor::{render_anchor, tagged_region},
+    comment::{find_documentation, BlockComment},
+    declaration::render_declaration,
+    prefix_hashes, FileContext, JavaDocable,
+};
+
+#[derive(Debug)]
+pub struct AnnotationElement<'a> {
+    comment: BlockComment<'a>,
+    node: Node<'a>,
+    context: &'a FileContext,
+}
+
+impl AnnotationElement<'_> {
+    /// Source text of the `default` value, if the element declares one
+    pub fn get_default(&self) -> Option<&str> {
+        let node = self.get_node();
+        let ctx = self.get_context();
+        node.child_by_field_name("value")
+            .map(|value| ctx.source_for_range(&value.range()))
+    }
+}
+
+impl<'a> JavaDocable<'a> for AnnotationElement<'a> {
+    fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let comment = find_documentation(node, ctx);
+        if let Some(comment) = comment {
+            Some(Self {
+                comment,
+                node,
+                context: ctx,
+            })
+        } else {
+            debug!("Found an annotation element but no block comment, skipping");
+            None
+        }
+    }
+
+    fn get_node(&self) -> Node<'_> {
+        self.node
+    }
+
+    fn get_context(&self) -> &'a FileContext {
+        self.context
+    }
+
+    fn get_comment(&self) -> &BlockComment<'a> {
+        &self.comment
+    }
+
+    fn get_name(&self) -> String {
+        let node = self.get_node();
+        let ctx = self.get_context();
+        let name = node.child_by_field_name("name").unwrap();
+        let name = ctx.source_for_range(&name.range());
+        format!("{name}()")
+    }
+
+    fn render(&'a self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let name = self.get_name();
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes}= {name}");
+        let declaration = render_declaration(self.get_node(), self.get_context());
+        let content = self.get_comment();
+        let content = format!("{content}");
+        let rendered = match self.get_default() {
+            Some(default) => {
+                let default_label = t!("default_value");
+                format!("\n\n{headline}\n\n{declaration}{content}\n{default_label}:: `{default}`\n")
+            }
+            None => format!("\n\n{headline}\n\n{declaration}{content}"),
+        };
+        tagged_region(self.get_node(), self.get_context(), rendered)
+    }
+}
@@ -0,0 +1,293 @@
+use rust_i18n::t;
+use tracing::{debug, instrument};
+use tree_sitter::Node;
+
+use crate::javadoc::{
+    annotation_element::AnnotationElement, class::Class, enumeration::Enum, interface::Interface,
+    prefix_hashes, record::Record,
+};
+
+use super::{
+    anchor::{render_anchor, tagged_region},
+    comment::{find_documentation, BlockComment},
+    declaration::render_declaration,
+    node_to_docable, FileContext, JavaDocable, JavaDocableElement,
+};
+
+#[derive(Debug)]
+pub struct AnnotationType<'a> {
+    comment: BlockComment<'a>,
+    node: Node<'a>,
+    context: &'a FileContext,
+    children: Vec<JavaDocableElement<'a>>,
+}
+
+impl AnnotationType<'_> {
+    /// Collects the values of a meta-annotation like `@Retention` or `@Target` on this declaration,
+    /// reduced to their simple names (`ElementType.METHOD` becomes `METHOD`)
+    pub fn meta_annotation_values(&self, annotation: &str) -> Option<Vec<String>> {
+        let node = self.get_node();
+        let ctx = self.get_context();
+        let mut cursor = node.walk();
+        let modifiers = node
+            .children(&mut cursor)
+            .find(|child| child.grammar_name() == "modifiers")?;
+
+        let mut cursor = modifiers.walk();
+        let found = modifiers.children(&mut cursor).find(|modifier| {
+            modifier.grammar_name() == "annotation"
+                && modifier
+                    .child_by_field_name("name")
+                    .map(|name| ctx.source_for_range(&name.range()))
+                    .is_some_and(|name| {
+                        name == annotation || name == format!("java.lang.annotation.{annotation}")
+                    })
+        })?;
+
+        let arguments = found.child_by_field_name("arguments")?;
+        let arguments = ctx.source_for_range(&arguments.range());
+        let arguments = arguments
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .trim();
+        let arguments = match arguments.split_once('=') {
+            Some((key, value)) if key.trim() == "value" => value.trim(),
+            _ => arguments,
+        };
+        let arguments = arguments.trim_start_matches('{').trim_end_matches('}');
+
+        let values = arguments
+            .split(',')
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.rsplit('.').next().unwrap_or(value).to_owned())
+            .collect();
+        Some(values)
+    }
+
+    fn render_meta_annotations(&self) -> String {
+        let mut lines: Vec<String> = vec![];
+        if let Some(retention) = self.meta_annotation_values("Retention") {
+            let label = t!("retention");
+            lines.push(format!("{label}:: {}", retention.join(", ")));
+        }
+        if let Some(target) = self.meta_annotation_values("Target") {
+            let label = t!("target");
+            lines.push(format!("{label}:: {}", target.join(", ")));
+        }
+        lines.join("\n")
+    }
+}
+
+impl<'a> AnnotationType<'a> {
+    pub fn get_children(&self) -> &[JavaDocableElement<'a>] {
+        &self.children
+    }
+}
+
+impl<'a> JavaDocable<'a> for AnnotationType<'a> {
+    #[instrument(skip_all)]
+    fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let comment = find_documentation(node, ctx);
+        let mut cursor = node.walk();
+        let body = node.child_by_field_name("body").unwrap();
+
+        let mut children: Vec<JavaDocableElement> = vec![];
+        for child in body.children(&mut cursor) {
+            if let Some(docable) = node_to_docable(child, ctx) {
+                children.push(docable);
+            }
+        }
+
+        if let Some(comment) = comment {
+            Some(Self {
+                comment,
+                node,
+                context: ctx,
+                children,
+            })
+        } else {
+            debug!("Found an annotation type but no block comment, skipping");
+            None
+        }
+    }
+
+    fn get_node(&self) -> Node<'_> {
+        self.node
+    }
+
+    fn get_context(&self) -> &'a FileContext {
+        self.context
+    }
+
+    fn get_comment(&self) -> &BlockComment<'a> {
+        &self.comment
+    }
+
+    fn get_name(&self) -> String {
+        let node = self.get_node();
+        let name = node.child_by_field_name("name").unwrap();
+        let ctx = self.context;
+        let name = ctx.source_for_range(&name.range());
+        name.to_owned()
+    }
+
+    fn render(&'a self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let name = self.get_name();
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes} @{name}");
+        let declaration = render_declaration(self.get_node(), self.get_context());
+        let content = self.get_comment();
+        let content = format!("{content}");
+        let meta_annotations = self.render_meta_annotations();
+
+        let mut elements: Vec<&AnnotationElement<'a>> = vec![];
+        let mut classes: Vec<&Class<'a>> = vec![];
+        let mut interfaces: Vec<&Interface<'a>> = vec![];
+        let mut enums: Vec<&Enum<'a>> = vec![];
+        let mut records: Vec<&Record<'a>> = vec![];
+        let mut annotations: Vec<&AnnotationType<'a>> = vec![];
+
+        // collect and group all children
+        for child in &self.children {
+            match child {
+                JavaDocableElement::AnnotationElement(element) => {
+                    elements.push(element);
+                }
+                JavaDocableElement::Class(class) => {
+                    classes.push(class);
+                }
+                JavaDocableElement::Interface(interface) => {
+                    interfaces.push(interface);
+                }
+                JavaDocableElement::Enum(en) => {
+                    enums.push(en);
+                }
+                JavaDocableElement::Record(record) => {
+                    records.push(record);
+                }
+                JavaDocableElement::AnnotationType(annotation) => {
+                    annotations.push(annotation);
+                }
+                _ => (),
+            }
+        }
+        let elements_headline = t!("annotation_element_headline", nesting = prefix_hashes);
+        let classes_headline = t!("class_headline", nesting = prefix_hashes);
+
+        //stringify all of the children with increased nesting levels
+        let elements: Vec<String> = elements
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        let elements = elements.join("\n");
+        let mut classes: Vec<String> = classes
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        classes.extend(interfaces.iter().map(|&child| child.render(level + 1)));
+        classes.extend(enums.iter().map(|&child| child.render(level + 1)));
+        classes.extend(records.iter().map(|&child| child.render(level + 1)));
+        classes.extend(annotations.iter().map(|&child| child.render(level + 1)));
+        let classes = classes.join("\n");
+
+        let rendered = format!(
+            "\n\n{headline}\n\n{declaration}{content}\n{meta_annotations}\n\n{elements_headline}\n\n{elements}{classes_headline}\n\n{classes}"
+        );
+        tagged_region(self.get_node(), self.get_context(), rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::parser::parse_string;
+
+    use super::*;
+
+    const JAVADOC: &str = "
+/** marks things */
+@Retention(RetentionPolicy.RUNTIME)
+@Target({ElementType.TYPE, ElementType.METHOD})
+public @interface Marker {
+  /** the name */
+  String name() default \"\";
+
+  /** the priority */
+  int priority();
+
+  String undocumented();
+}";
+
+    #[test]
+    fn javadoc_comment() {
+        let sourcecode = JAVADOC;
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let filecontext = FileContext::from_str(sourcecode).unwrap();
+
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &filecontext))
+            .collect();
+        assert_eq!(children.len(), 1);
+        let child = children.first().unwrap();
+        match child {
+            JavaDocableElement::AnnotationType(child) => {
+                assert_eq!(child.get_name(), "Marker");
+                assert_eq!(child.children.len(), 2);
+                assert_eq!(
+                    child.meta_annotation_values("Retention"),
+                    Some(vec!["RUNTIME".to_owned()])
+                );
+                assert_eq!(
+                    child.meta_annotation_values("Target"),
+                    Some(vec!["TYPE".to_owned(), "METHOD".to_owned()])
+                );
+                match child.children.first().unwrap() {
+                    JavaDocableElement::AnnotationElement(element) => {
+                        assert_eq!(element.get_name(), "name()");
+                        assert_eq!(element.get_default(), Some("\"\""));
+                    }
+                    _ => panic!("Got something else than an annotation element????"),
+                }
+            }
+            _ => panic!("Got something else than an annotation type????"),
+        }
+    }
+
+    #[test]
+    fn renders_nested_annotation_types() {
+        for header in ["class Outer {", "enum Outer {;", "record Outer() {"] {
+            let sourcecode = format!(
+                "
+/** the outer type */
+public {header}
+  /** marks nested things */
+  public @interface Nested {{}}
+}}"
+            );
+            let tree = parse_string(&sourcecode).unwrap();
+            let root = tree.root_node();
+            let mut cursor = root.walk();
+            let filecontext = FileContext::from_str(&sourcecode).unwrap();
+
+            let children: Vec<JavaDocableElement> = root
+                .children(&mut cursor)
+                .filter_map(|node| node_to_docable(node, &filecontext))
+                .collect();
+            let rendered = children.first().unwrap().as_docable().render(0);
+            assert!(rendered.contains("== @Nested"), "{header}: {rendered}");
+            assert!(
+                rendered.contains("marks nested things"),
+                "{header}: {rendered}"
+            );
+        }
+    }
+}
@@ -2,10 +2,15 @@ use rust_i18n::t;
 use tracing::{debug, instrument};
 use tree_sitter::Node;
 
-use crate::javadoc::{constructor::Constructor, field::Field, method::Method, prefix_hashes};
+use crate::javadoc::{
+    annotation_type::AnnotationType, constructor::Constructor, enumeration::Enum, field::Field,
+    interface::Interface, method::Method, prefix_hashes, record::Record,
+};
 
 use super::{
-    comment::{find_block_comment, BlockComment},
+    anchor::{render_anchor, tagged_region},
+    comment::{find_documentation, BlockComment},
+    declaration::render_declaration,
     node_to_docable, FileContext, JavaDocable, JavaDocableElement,
 };
 
@@ -17,13 +22,19 @@ pub struct Class<'a> {
     children: Vec<JavaDocableElement<'a>>,
 }
 
+impl<'a> Class<'a> {
+    pub fn get_children(&self) -> &[JavaDocableElement<'a>] {
+        &self.children
+    }
+}
+
 impl<'a> JavaDocable<'a> for Class<'a> {
     #[instrument(skip_all)]
     fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
     where
         Self: Sized,
     {
-        let comment = find_block_comment(node, ctx);
+        let comment = find_documentation(node, ctx);
         let mut cursor = node.walk();
         let body = node.child_by_field_name("body").unwrap();
 
@@ -66,13 +77,19 @@ impl<'a> JavaDocable<'a> for Class<'a> {
     fn render(&'a self, level: u8) -> String {
         let prefix_hashes = prefix_hashes(level);
         let name = self.get_name();
-        let headline = format!("{prefix_hashes} {name}");
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes} {name}");
+        let declaration = render_declaration(self.get_node(), self.get_context());
         let content = self.get_comment();
         let content = format!("{content}");
 
         let mut fields: Vec<&Field<'a>> = vec![];
         let mut methods: Vec<&Method<'a>> = vec![];
         let mut classes: Vec<&Class<'a>> = vec![];
+        let mut interfaces: Vec<&Interface<'a>> = vec![];
+        let mut enums: Vec<&Enum<'a>> = vec![];
+        let mut records: Vec<&Record<'a>> = vec![];
+        let mut annotations: Vec<&AnnotationType<'a>> = vec![];
         let mut constructors: Vec<&Constructor<'a>> = vec![];
 
         // collect and group all children
@@ -90,7 +107,22 @@ impl<'a> JavaDocable<'a> for Class<'a> {
                 JavaDocableElement::Constructor(constructor) => {
                     constructors.push(constructor);
                 }
-                JavaDocableElement::Interface(_) => (),
+                JavaDocableElement::Enum(en) => {
+                    enums.push(en);
+                }
+                JavaDocableElement::Record(record) => {
+                    records.push(record);
+                }
+                JavaDocableElement::Interface(interface) => {
+                    interfaces.push(interface);
+                }
+                JavaDocableElement::EnumConstant(_) => (),
+                JavaDocableElement::AnnotationType(annotation) => {
+                    annotations.push(annotation);
+                }
+                JavaDocableElement::AnnotationElement(_) => (),
+                JavaDocableElement::Package(_) => (),
+                JavaDocableElement::Module(_) => (),
             }
         }
         let fields_headline = t!("field_headline", nesting = prefix_hashes);
@@ -109,10 +141,14 @@ impl<'a> JavaDocable<'a> for Class<'a> {
             .map(|&child| child.render(level + 1))
             .collect();
         let methods = methods.join("\n");
-        let classes: Vec<String> = classes
+        let mut classes: Vec<String> = classes
             .iter()
             .map(|&child| child.render(level + 1))
             .collect();
+        classes.extend(interfaces.iter().map(|&child| child.render(level + 1)));
+        classes.extend(enums.iter().map(|&child| child.render(level + 1)));
+        classes.extend(records.iter().map(|&child| child.render(level + 1)));
+        classes.extend(annotations.iter().map(|&child| child.render(level + 1)));
         let classes = classes.join("\n");
         let constructors: Vec<String> = constructors
             .iter()
@@ -120,10 +156,11 @@ impl<'a> JavaDocable<'a> for Class<'a> {
             .collect();
         let constructors = constructors.join("\n");
 
-        format!("\n\n{headline}\n\n{content}{constructors_headline}\n\n{constructors}{fields_headline}\n\n{fields}{methods_headline}\n\n{methods}{classes_headline}\n\n{classes}")
+        let rendered = format!("\n\n{headline}\n\n{declaration}{content}{constructors_headline}\n\n{constructors}{fields_headline}\n\n{fields}{methods_headline}\n\n{methods}{classes_headline}\n\n{classes}");
+        tagged_region(self.get_node(), self.get_context(), rendered)
     }
 
-    fn get_comment(&self) -> &'a BlockComment {
+    fn get_comment(&self) -> &BlockComment<'a> {
         &self.comment
     }
 }
@@ -1,34 +1,168 @@
 use core::fmt;
 
+use rust_i18n::t;
+
 use tracing::{debug, instrument, trace};
 use tree_sitter::Node;
 
-use super::FileContext;
+use self::{
+    html::{html_to_adoc, html_to_markdown},
+    markdown::{markdown_references, markdown_to_adoc, strip_markdown_lines},
+};
+
+use super::{
+    inheritance::inherit_javadoc,
+    inline::convert_inline_tags,
+    snippet::{protect_snippets, restore_snippets},
+    tags::{BlockTag, Javadoc},
+    FileContext, TextFormat,
+};
+
+mod html;
+mod markdown;
+
+/// Syntax of a documentation comment
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CommentKind {
+    /// classic `/** */` comment with html markup
+    Javadoc,
+    /// consecutive `///` line comments with markdown markup
+    Markdown,
+    /// no comment at all, for undocumented declarations and those documented by inheritance only
+    Missing,
+}
 
+/// A documentation comment, either a classic `/** */` javadoc comment or a markdown comment
+/// made of consecutive `///` line comments
 #[derive(Debug)]
 pub struct BlockComment<'a> {
+    /// first line comment of a markdown comment, the same as `node` for javadoc comments
+    first: Node<'a>,
+    /// the comment node directly in front of the documented declaration, or the declaration
+    /// itself for missing comments
     node: Node<'a>,
+    kind: CommentKind,
     context: &'a FileContext,
 }
 
 impl<'a> BlockComment<'a> {
     pub fn new(node: Node<'a>, context: &'a FileContext) -> Self {
-        Self { node, context }
+        Self {
+            first: node,
+            node,
+            kind: CommentKind::Javadoc,
+            context,
+        }
+    }
+
+    /// A markdown comment spanning the line comments from `first` to `last`
+    pub fn markdown(first: Node<'a>, last: Node<'a>, context: &'a FileContext) -> Self {
+        Self {
+            first,
+            node: last,
+            kind: CommentKind::Markdown,
+            context,
+        }
+    }
+
+    /// An empty comment of the undocumented `declaration`, which may still inherit documentation
+    pub fn missing(declaration: Node<'a>, context: &'a FileContext) -> Self {
+        Self {
+            first: declaration,
+            node: declaration,
+            kind: CommentKind::Missing,
+            context,
+        }
+    }
+
+    /// The documented declaration
+    pub fn declaration(&self) -> Option<Node<'a>> {
+        match self.kind {
+            CommentKind::Missing => Some(self.node),
+            // the documented declaration directly follows its comment
+            CommentKind::Javadoc | CommentKind::Markdown => self.node.next_named_sibling(),
+        }
+    }
+
+    /// Parses the comment into its description and block tags
+    pub fn javadoc(&self) -> Javadoc {
+        let source = &self.context.source[self.first.start_byte()..self.node.end_byte()];
+        match self.kind {
+            CommentKind::Javadoc => Javadoc::parse(strip_comment_lines(source)),
+            CommentKind::Markdown => Javadoc::parse(strip_markdown_lines(source)),
+            CommentKind::Missing => Javadoc::default(),
+        }
+    }
+
+    /// Like [BlockComment::javadoc], but with the html or markdown markup converted to the text
+    /// format of the file context. Inline tags are left for [BlockComment::rendered_javadoc].
+    pub fn converted_javadoc(&self) -> Javadoc {
+        let mut javadoc = self.javadoc();
+        // `@see` takes the same references as `{@link}`, apart from quoted strings and html links
+        for tag in javadoc.tags.iter_mut() {
+            if let BlockTag::See(reference) = tag {
+                if !reference.starts_with(['"', '<']) {
+                    *reference = format!("{{@link {reference}}}");
+                }
+            }
+        }
+        let convert = match (self.kind, self.context.text_format()) {
+            (CommentKind::Markdown, TextFormat::Asciidoc) => markdown_to_adoc,
+            (CommentKind::Markdown, TextFormat::Markdown) => markdown_references,
+            (CommentKind::Javadoc | CommentKind::Missing, TextFormat::Asciidoc) => html_to_adoc,
+            (CommentKind::Javadoc | CommentKind::Missing, TextFormat::Markdown) => html_to_markdown,
+        };
+        javadoc.map_text(|text| {
+            // snippet bodies are code, not markup
+            let (text, snippets) = protect_snippets(text);
+            restore_snippets(&convert(&text), &snippets)
+        })
+    }
+
+    /// Like [BlockComment::javadoc], but with all text already converted to the text format of the
+    /// file context and the documentation of overridden methods inherited
+    pub fn rendered_javadoc(&self) -> Javadoc {
+        let scope = self.declaration();
+        let javadoc = self.converted_javadoc();
+        let mut javadoc = match scope {
+            Some(scope) => inherit_javadoc(javadoc, scope, self.context),
+            None => javadoc,
+        };
+        if self.kind == CommentKind::Missing && javadoc.description.trim().is_empty() {
+            javadoc.description = t!("no_description").to_string();
+        }
+        javadoc.map_text(|text| convert_inline_tags(text, scope, self.context))
+    }
+
+    /// Renders the comment without the block tags named in `skipped`, for elements that lay those
+    /// tags out on their own
+    pub fn render_without(&self, skipped: &[&str]) -> String {
+        let doc = render_javadoc_without(&self.rendered_javadoc(), skipped);
+        format!("{doc}\n\n")
     }
 }
 
 impl fmt::Display for BlockComment<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let node = self.node;
-        let range = node.range();
-        let ctx = self.context;
-        let source = &ctx.0;
-        let source = &source[range.start_byte..range.end_byte];
-        let doc = javadoc_to_adoc(source);
-        writeln!(f, "{doc}")
+        let doc = render_javadoc(&self.rendered_javadoc());
+        write!(f, "{doc}\n\n")
     }
 }
 
+/// The documentation comment of `node`, or an empty one when undocumented declarations are
+/// included in the documentation
+pub fn find_documentation<'a>(
+    node: Node<'a>,
+    context: &'a FileContext,
+) -> Option<BlockComment<'a>> {
+    find_block_comment(node, context).or_else(|| {
+        context
+            .selection()
+            .include_undocumented
+            .then(|| BlockComment::missing(node, context))
+    })
+}
+
 pub fn find_block_comment<'a>(
     node: Node<'a>,
     context: &'a FileContext,
@@ -39,7 +173,7 @@ pub fn find_block_comment<'a>(
             let name = sibling.grammar_name();
             if name == "block_comment" {
                 debug!("Found a sibling block_comment");
-                let sourcecode = &context.0;
+                let sourcecode = &context.source;
                 let start = sibling.range();
                 let start = start.start_byte;
                 let end = start + 3;
@@ -52,6 +186,18 @@ pub fn find_block_comment<'a>(
                 } else {
                     None
                 }
+            } else if is_markdown_line(sibling, context) {
+                debug!("Is markdown documentation");
+                let mut first = sibling;
+                while let Some(previous) = first.prev_sibling() {
+                    // the lines of a markdown comment directly follow each other
+                    let adjacent = previous.end_position().row + 1 == first.start_position().row;
+                    if !adjacent || !is_markdown_line(previous, context) {
+                        break;
+                    }
+                    first = previous;
+                }
+                Some(BlockComment::markdown(first, sibling, context))
             } else {
                 None
             }
@@ -60,50 +206,70 @@ pub fn find_block_comment<'a>(
     }
 }
 
-#[instrument(skip_all)]
-fn javadoc_to_adoc(source: &str) -> String {
-    let lines: Vec<String> = source
+/// Whether `node` is a `///` line comment of a markdown documentation comment
+fn is_markdown_line(node: Node<'_>, context: &FileContext) -> bool {
+    node.grammar_name() == "line_comment"
+        && context.source_for_range(&node.range()).starts_with("///")
+}
+
+/// Strips the comment delimiters and leading asterisks from every line of a javadoc comment.
+/// Indentation behind the asterisk is kept, apart from the single separating space.
+fn strip_comment_lines(source: &str) -> Vec<String> {
+    let source = source.trim();
+    let source = source.strip_prefix("/**").unwrap_or(source);
+    let source = source.strip_suffix("*/").unwrap_or(source);
+    source
         .lines()
         .map(|line| {
-            let line = line.trim();
-            let line = line.replace("<p>", "");
-            if line.starts_with("/**") {
-                trace!("Stripping '/**' from {line:?}");
-                return line.strip_prefix("/**").unwrap().trim_start().to_owned();
-            } else if line.starts_with("*/") {
-                trace!("Stripping '*/' from {line:?}");
-                return line.strip_prefix("*/").unwrap().trim_start().to_owned();
-            } else if line.starts_with("*") {
+            let line = line.trim_end();
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('*') {
                 trace!("Stripping '*' from {line:?}");
-                return line.strip_prefix("*").unwrap().trim_start().to_owned();
-            } else {
-                return line;
-            }
-        })
-        .map(|line| {
-            if line.starts_with("@") {
-                trace!("Found an @ annotation in line {line:?}");
-                let line = line.strip_prefix("@").unwrap();
-                let first_space = line.find(" ");
-                if let Some(first_space) = first_space {
-                    let head = &line[..first_space];
-                    let tail = &line[first_space..];
-                    trace!("Head: {head:?} | Tail: {tail:?}");
-                    format!("{head}::{tail}")
-                } else {
-                    line.to_string()
-                }
+                let trimmed = trimmed.trim_start_matches('*');
+                trimmed.strip_prefix(' ').unwrap_or(trimmed).to_owned()
             } else {
-                line.to_string()
+                trimmed.to_owned()
             }
         })
-        .collect();
+        .collect()
+}
+
+/// Renders the description followed by every block tag as `tag:: content`
+fn render_javadoc(javadoc: &Javadoc) -> String {
+    render_javadoc_without(javadoc, &[])
+}
 
+/// Like [render_javadoc], but leaves out the block tags named in `skipped`
+fn render_javadoc_without(javadoc: &Javadoc, skipped: &[&str]) -> String {
+    let mut lines: Vec<String> = vec![];
+    if !javadoc.description.is_empty() {
+        lines.push(javadoc.description.clone());
+    }
+    for tag in javadoc
+        .tags
+        .iter()
+        .filter(|tag| !skipped.contains(&tag.name()))
+    {
+        let name = tag.name();
+        let content = tag.content();
+        trace!("Rendering block tag {name:?}");
+        lines.push(format!("{name}:: {content}"));
+    }
     lines.join("\n")
 }
 
+#[instrument(skip_all)]
+fn javadoc_to_adoc(source: &str) -> String {
+    let javadoc = Javadoc::parse(strip_comment_lines(source));
+    render_javadoc(&javadoc.map_text(html_to_adoc))
+}
+
 #[cfg(test)]
 mod javadoc_to_adoc_tests {
+    use std::str::FromStr;
+
+    use crate::{javadoc::node_to_docable, parser::parse_string};
+
     use super::*;
 
     #[test]
@@ -112,4 +278,66 @@ mod javadoc_to_adoc_tests {
         let result = javadoc_to_adoc(input);
         assert_eq!(result, "some cool documentation");
     }
+
+    #[test]
+    fn strips_single_line_comment() {
+        let input = "/** some cool documentation */";
+        let result = javadoc_to_adoc(input);
+        assert_eq!(result, "some cool documentation");
+    }
+
+    #[test]
+    fn renders_block_tags() {
+        let input = "/**
+ * Does things.
+ * @param  url  an absolute URL
+ * @return something
+ */";
+        let result = javadoc_to_adoc(input);
+        assert_eq!(
+            result,
+            "Does things.\nparam:: url an absolute URL\nreturn:: something"
+        );
+    }
+
+    #[test]
+    fn finds_markdown_comments() {
+        let source = "
+class A {
+  // not documentation
+  /// Does *things*.
+  ///
+  /// @param a the `a`
+  void foo(int a) {}
+}";
+        let tree = parse_string(source).unwrap();
+        let ctx = FileContext::from_str(source).unwrap();
+        let class = tree.root_node().named_child(0).unwrap();
+        let body = class.child_by_field_name("body").unwrap();
+        let method = body.named_child(4).unwrap();
+        let comment = find_block_comment(method, &ctx).unwrap();
+        assert_eq!(
+            comment.to_string(),
+            "Does __things__.\nparam:: a the `+a+`\n\n"
+        );
+    }
+
+    #[test]
+    fn separates_comments_from_headlines() {
+        let source = "
+/**
+ * A thing.
+ * @since 1.0
+ */
+class A {
+  /** Creates it. */
+  A() {}
+}";
+        let tree = parse_string(source).unwrap();
+        let ctx = FileContext::from_str(source).unwrap();
+        let class = tree.root_node().named_child(1).unwrap();
+        let class = node_to_docable(class, &ctx).unwrap();
+        let rendered = class.as_docable().render(0);
+        assert!(rendered.contains("since:: 1.0\n\n== Constructors"));
+    }
 }
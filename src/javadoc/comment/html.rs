@@ -0,0 +1,845 @@
+//! Conversion of the html markup found in javadoc comments into native asciidoc or markdown
+
+use tracing::trace;
+
+use crate::javadoc::{inline::closing_brace, TextFormat};
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Text(&'a str),
+    /// An inline tag like `{@code ...}`, which is converted later on and must be kept as is
+    InlineTag(&'a str),
+    Start {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    End {
+        name: String,
+    },
+}
+
+/// Splits an html fragment into text, inline tags and html tags. Anything looking like a tag but
+/// not parsing as one is kept as text.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut text_start = 0;
+    let mut index = 0;
+    let bytes = html.as_bytes();
+
+    while index < bytes.len() {
+        let rest = &html[index..];
+        let token = if rest.starts_with("{@") {
+            closing_brace(rest).map(|end| (Token::InlineTag(&rest[..=end]), end + 1))
+        } else if rest.starts_with("<!--") {
+            rest.find("-->")
+                .map(|end| (Token::Text(""), end + "-->".len()))
+        } else if rest.starts_with('<') {
+            parse_tag(rest)
+        } else {
+            None
+        };
+
+        match token {
+            Some((token, length)) => {
+                if text_start < index {
+                    tokens.push(Token::Text(&html[text_start..index]));
+                }
+                if token != Token::Text("") {
+                    tokens.push(token);
+                }
+                index += length;
+                text_start = index;
+            }
+            None => {
+                index += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+            }
+        }
+    }
+    if text_start < html.len() {
+        tokens.push(Token::Text(&html[text_start..]));
+    }
+    tokens
+}
+
+/// Parses a start or end tag at the beginning of `rest`, returning it and its length
+fn parse_tag(rest: &str) -> Option<(Token<'static>, usize)> {
+    let end = rest.find('>')?;
+    let inner = &rest[1..end];
+    let (is_end, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let inner = inner.strip_suffix('/').unwrap_or(inner);
+    let name_end = inner
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(inner.len());
+    let name = &inner[..name_end];
+    if name.is_empty() || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = name.to_ascii_lowercase();
+    let attributes = &inner[name_end..];
+    if !attributes.is_empty() && !attributes.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let token = if is_end {
+        Token::End { name }
+    } else {
+        Token::Start {
+            name,
+            attributes: parse_attributes(attributes),
+        }
+    };
+    Some((token, end + 1))
+}
+
+fn parse_attributes(attributes: &str) -> Vec<(String, String)> {
+    let mut result: Vec<(String, String)> = vec![];
+    let mut rest = attributes.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (value, remaining) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let after = &after[1..];
+                    let end = after.find(quote).unwrap_or(after.len());
+                    (&after[..end], after.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            rest = remaining.trim_start();
+            value.to_owned()
+        } else {
+            String::new()
+        };
+        if !name.is_empty() {
+            result.push((name, value));
+        }
+    }
+    result
+}
+
+/// Decodes html entities into text safe to use in asciidoc, using the built-in attributes for
+/// characters with a meaning in asciidoc. Markdown keeps the entities of such characters.
+fn decode_entities(text: &str, raw: bool, format: TextFormat) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let decoded = candidate
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&candidate[1..end], raw, format).map(|c| (c, end + 1)));
+        match decoded {
+            Some((decoded, length)) => {
+                result.push_str(&decoded);
+                rest = &candidate[length..];
+            }
+            None => {
+                result.push('&');
+                rest = &candidate[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn decode_entity(entity: &str, raw: bool, format: TextFormat) -> Option<String> {
+    let c = match entity {
+        "lt" => '<',
+        "gt" => '>',
+        "amp" => '&',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "mdash" => '—',
+        "ndash" => '–',
+        "hellip" => '…',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "laquo" => '«',
+        "raquo" => '»',
+        "times" => '×',
+        "middot" => '·',
+        "sect" => '§',
+        "deg" => '°',
+        "le" => '≤',
+        "ge" => '≥',
+        "ne" => '≠',
+        "larr" => '←',
+        "rarr" => '→',
+        _ => {
+            let number = entity.strip_prefix('#')?;
+            let number = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(number)?
+        }
+    };
+    if raw {
+        return Some(c.to_string());
+    }
+    let escaped = match (c, format) {
+        ('<', TextFormat::Asciidoc) => "{lt}".to_owned(),
+        ('>', TextFormat::Asciidoc) => "{gt}".to_owned(),
+        ('&', TextFormat::Asciidoc) => "{amp}".to_owned(),
+        ('\u{a0}', TextFormat::Asciidoc) => "{nbsp}".to_owned(),
+        ('<', TextFormat::Markdown) => "&lt;".to_owned(),
+        ('>', TextFormat::Markdown) => "&gt;".to_owned(),
+        ('&', TextFormat::Markdown) => "&amp;".to_owned(),
+        ('\u{a0}', TextFormat::Markdown) => "&nbsp;".to_owned(),
+        (c, _) => c.to_string(),
+    };
+    Some(escaped)
+}
+
+#[derive(Debug)]
+enum Frame {
+    Root,
+    ListItem,
+    Term,
+    Definition,
+    Link(String),
+    Heading(usize),
+    Quote,
+    Cell { header: bool },
+    Caption,
+}
+
+#[derive(Debug, Default)]
+struct Table {
+    caption: String,
+    rows: Vec<Vec<(bool, String)>>,
+    row: Option<Vec<(bool, String)>>,
+}
+
+#[derive(Debug)]
+struct Converter {
+    /// open elements collecting their content, each with its output buffer
+    frames: Vec<(Frame, String)>,
+    /// open lists, `true` for ordered ones
+    lists: Vec<bool>,
+    tables: Vec<Table>,
+    pre: bool,
+    format: TextFormat,
+}
+
+impl Converter {
+    fn new(format: TextFormat) -> Self {
+        Self {
+            frames: vec![(Frame::Root, String::new())],
+            lists: vec![],
+            tables: vec![],
+            pre: false,
+            format,
+        }
+    }
+
+    /// Picks the markup of the target format
+    fn markup<'a>(&self, asciidoc: &'a str, markdown: &'a str) -> &'a str {
+        match self.format {
+            TextFormat::Asciidoc => asciidoc,
+            TextFormat::Markdown => markdown,
+        }
+    }
+
+    /// Line opening and closing listings
+    fn listing_delimiter(&self) -> &'static str {
+        self.markup("----", "```")
+    }
+
+    fn push(&mut self, text: &str) {
+        // there is always at least the root frame
+        self.frames.last_mut().unwrap().1.push_str(text);
+    }
+
+    /// Appends `line` on a line of its own
+    fn push_line(&mut self, line: &str) {
+        let buffer = &self.frames.last().unwrap().1;
+        if !buffer.is_empty() && !buffer.ends_with('\n') {
+            self.push("\n");
+        }
+        self.push(line);
+        self.push("\n");
+    }
+
+    fn open(&mut self, frame: Frame) {
+        self.frames.push((frame, String::new()));
+    }
+
+    fn is_open(&self, matches: impl Fn(&Frame) -> bool) -> bool {
+        self.frames.iter().any(|(frame, _)| matches(frame))
+    }
+
+    /// Closes frames until one matching `matches` is closed, if there is one
+    fn close_until(&mut self, matches: impl Fn(&Frame) -> bool) {
+        if !self.is_open(&matches) {
+            return;
+        }
+        while let Some((frame, _)) = self.frames.last() {
+            let done = matches(frame);
+            self.close();
+            if done {
+                break;
+            }
+        }
+    }
+
+    /// Closes the innermost frame, appending its converted content to its parent
+    fn close(&mut self) {
+        if self.frames.len() <= 1 {
+            return;
+        }
+        let (frame, content) = self.frames.pop().unwrap();
+        let inline = collapse_whitespace(&content);
+        match frame {
+            Frame::Root => (),
+            Frame::ListItem => {
+                let ordered = self.lists.last().copied().unwrap_or(false);
+                let depth = self.lists.len().max(1);
+                let item = match self.format {
+                    TextFormat::Asciidoc => {
+                        let marker = if ordered { "." } else { "*" };
+                        format!("{} {inline}", marker.repeat(depth))
+                    }
+                    TextFormat::Markdown => {
+                        let marker = if ordered { "1." } else { "-" };
+                        format!("{}{marker} {inline}", "    ".repeat(depth - 1))
+                    }
+                };
+                self.push_line(&item);
+            }
+            Frame::Term => {
+                // the definition follows on the same line
+                let term = match self.format {
+                    TextFormat::Asciidoc => format!("{inline}::"),
+                    TextFormat::Markdown => format!("**{inline}**:"),
+                };
+                self.push_line(&term);
+                self.frames.last_mut().unwrap().1.pop();
+            }
+            Frame::Definition => {
+                let separator = self.markup("\n", "\n\n");
+                self.push(&format!(" {inline}{separator}"))
+            }
+            Frame::Link(href) => {
+                let label = if inline.is_empty() {
+                    href.clone()
+                } else {
+                    inline.replace(']', "\\]")
+                };
+                let link = match (self.format, href.strip_prefix('#')) {
+                    (TextFormat::Asciidoc, Some(anchor)) => format!("<<{anchor},{label}>>"),
+                    (TextFormat::Asciidoc, None) => format!("link:{href}[{label}]"),
+                    (TextFormat::Markdown, _) => format!("[{label}]({href})"),
+                };
+                self.push(&link);
+            }
+            Frame::Heading(level) => {
+                let heading = match self.format {
+                    TextFormat::Asciidoc => {
+                        format!("[discrete]\n{} {inline}", "=".repeat(level + 1))
+                    }
+                    TextFormat::Markdown => format!("{} {inline}", "#".repeat((level + 1).min(6))),
+                };
+                self.push(&format!("\n\n{heading}\n\n"));
+            }
+            Frame::Quote => {
+                let quote = match self.format {
+                    TextFormat::Asciidoc => format!("____\n{}\n____", content.trim()),
+                    TextFormat::Markdown => {
+                        let lines: Vec<String> = content
+                            .trim()
+                            .lines()
+                            .map(|line| format!("> {}", line.trim()).trim_end().to_owned())
+                            .collect();
+                        lines.join("\n")
+                    }
+                };
+                self.push(&format!("\n\n{quote}\n\n"));
+            }
+            Frame::Cell { header } => {
+                // markdown table cells can't span lines
+                let cell = match self.format {
+                    TextFormat::Asciidoc => content.trim().to_owned(),
+                    TextFormat::Markdown => inline,
+                };
+                let cell = cell.replace('|', "\\|");
+                if let Some(table) = self.tables.last_mut() {
+                    table.row.get_or_insert_with(Vec::new).push((header, cell));
+                }
+            }
+            Frame::Caption => {
+                if let Some(table) = self.tables.last_mut() {
+                    table.caption = inline;
+                }
+            }
+        }
+    }
+
+    fn close_list_item(&mut self) {
+        if matches!(self.frames.last(), Some((Frame::ListItem, _))) {
+            self.close();
+        }
+    }
+
+    fn finish_row(&mut self) {
+        self.close_until(|frame| matches!(frame, Frame::Cell { .. }));
+        if let Some(table) = self.tables.last_mut() {
+            if let Some(row) = table.row.take() {
+                table.rows.push(row);
+            }
+        }
+    }
+
+    fn finish_table(&mut self) {
+        self.finish_row();
+        let Some(table) = self.tables.pop() else {
+            return;
+        };
+        if table.rows.is_empty() {
+            return;
+        }
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(1);
+        let has_header = table.rows[0].iter().all(|(header, _)| *header);
+        if self.format == TextFormat::Markdown {
+            self.push(&markdown_table(&table, columns, has_header));
+            return;
+        }
+        let mut result = String::from("\n\n");
+        if !table.caption.is_empty() {
+            result.push_str(&format!(".{}\n", table.caption));
+        }
+        let cols = vec!["1"; columns].join(",");
+        if has_header {
+            result.push_str(&format!("[cols=\"{cols}\",options=\"header\"]\n"));
+        } else {
+            result.push_str(&format!("[cols=\"{cols}\"]\n"));
+        }
+        result.push_str("|===\n");
+        for row in &table.rows {
+            for index in 0..columns {
+                let cell = row.get(index).map(|(_, cell)| cell.as_str()).unwrap_or("");
+                result.push_str(&format!("|{cell}\n"));
+            }
+            result.push('\n');
+        }
+        result.push_str("|===\n\n");
+        self.push(&result);
+    }
+
+    fn start(&mut self, name: &str, attributes: &[(String, String)]) {
+        let attribute = |key: &str| {
+            attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str())
+        };
+        match name {
+            "p" | "div" => self.push("\n\n"),
+            "br" => self.push(self.markup(" +\n", "\\\n")),
+            "hr" => self.push(self.markup("\n\n'''\n\n", "\n\n---\n\n")),
+            "b" | "strong" => self.push("**"),
+            "i" | "em" | "cite" | "var" => self.push(self.markup("__", "_")),
+            "code" | "tt" | "kbd" | "samp" => self.push(self.markup("``", "`")),
+            "u" => self.push(self.markup("[.underline]#", "<u>")),
+            "s" | "del" | "strike" => self.push(self.markup("[.line-through]#", "~~")),
+            "sup" => self.push(self.markup("^", "<sup>")),
+            "sub" => self.push(self.markup("~", "<sub>")),
+            "span" | "font" | "small" | "big" | "center" => (),
+            "pre" => {
+                self.pre = true;
+                self.push(&format!("\n\n{}\n", self.listing_delimiter()));
+            }
+            "ul" | "ol" => {
+                // a nested list ends the text of the item it belongs to
+                self.close_list_item();
+                // nested lists directly follow their parent item
+                if self.lists.is_empty() {
+                    self.push("\n\n");
+                }
+                self.lists.push(name == "ol");
+            }
+            "li" => {
+                self.close_list_item();
+                self.open(Frame::ListItem);
+            }
+            "dl" => self.push("\n\n"),
+            "dt" => {
+                self.close_until(|frame| matches!(frame, Frame::Definition | Frame::Term));
+                self.open(Frame::Term);
+            }
+            "dd" => {
+                self.close_until(|frame| matches!(frame, Frame::Definition | Frame::Term));
+                self.open(Frame::Definition);
+            }
+            "a" => match (attribute("href"), attribute("name").or(attribute("id"))) {
+                (Some(href), _) => {
+                    let href = decode_entities(href, true, self.format);
+                    self.open(Frame::Link(href));
+                }
+                (None, Some(anchor)) => match self.format {
+                    TextFormat::Asciidoc => self.push(&format!("[[{anchor}]]")),
+                    TextFormat::Markdown => self.push(&format!("<a id=\"{anchor}\"></a>")),
+                },
+                (None, None) => (),
+            },
+            "img" => {
+                let src = decode_entities(attribute("src").unwrap_or_default(), true, self.format);
+                let alt = attribute("alt").unwrap_or_default();
+                match self.format {
+                    TextFormat::Asciidoc => self.push(&format!("image:{src}[{alt}]")),
+                    TextFormat::Markdown => self.push(&format!("![{alt}]({src})")),
+                }
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse().unwrap_or(1);
+                self.open(Frame::Heading(level));
+            }
+            "blockquote" => self.open(Frame::Quote),
+            "table" => self.tables.push(Table::default()),
+            "caption" => self.open(Frame::Caption),
+            "tr" => self.finish_row(),
+            "td" | "th" => {
+                self.close_until(|frame| matches!(frame, Frame::Cell { .. }));
+                self.open(Frame::Cell {
+                    header: name == "th",
+                });
+            }
+            "thead" | "tbody" | "tfoot" => (),
+            _ => {
+                trace!("Passing through unknown html tag {name:?}");
+                let attributes: Vec<String> = attributes
+                    .iter()
+                    .map(|(key, value)| format!(" {key}=\"{value}\""))
+                    .collect();
+                let attributes = attributes.join("");
+                let (lt, gt) = (self.markup("{lt}", "&lt;"), self.markup("{gt}", "&gt;"));
+                self.push(&format!("{lt}{name}{attributes}{gt}"));
+            }
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match name {
+            "p" | "div" => self.push("\n\n"),
+            "b" | "strong" => self.push("**"),
+            "i" | "em" | "cite" | "var" => self.push(self.markup("__", "_")),
+            "code" | "tt" | "kbd" | "samp" => self.push(self.markup("``", "`")),
+            "u" => self.push(self.markup("#", "</u>")),
+            "s" | "del" | "strike" => self.push(self.markup("#", "~~")),
+            "sup" => self.push(self.markup("^", "</sup>")),
+            "sub" => self.push(self.markup("~", "</sub>")),
+            "span" | "font" | "small" | "big" | "center" | "br" | "hr" | "img" => (),
+            "pre" => {
+                self.pre = false;
+                let buffer = &mut self.frames.last_mut().unwrap().1;
+                let trimmed = buffer.trim_end().len();
+                buffer.truncate(trimmed);
+                self.push(&format!("\n{}\n\n", self.listing_delimiter()));
+            }
+            "ul" | "ol" => {
+                self.close_list_item();
+                self.lists.pop();
+                self.push("\n");
+            }
+            "li" => self.close_list_item(),
+            "dt" => self.close_until(|frame| matches!(frame, Frame::Term)),
+            "dd" => self.close_until(|frame| matches!(frame, Frame::Definition)),
+            "dl" => {
+                self.close_until(|frame| matches!(frame, Frame::Definition | Frame::Term));
+                self.push("\n\n");
+            }
+            "a" => self.close_until(|frame| matches!(frame, Frame::Link(_))),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.close_until(|frame| matches!(frame, Frame::Heading(_)))
+            }
+            "blockquote" => self.close_until(|frame| matches!(frame, Frame::Quote)),
+            "caption" => self.close_until(|frame| matches!(frame, Frame::Caption)),
+            "td" | "th" => self.close_until(|frame| matches!(frame, Frame::Cell { .. })),
+            "tr" => self.finish_row(),
+            "table" => self.finish_table(),
+            "thead" | "tbody" | "tfoot" => (),
+            _ => {
+                let (lt, gt) = (self.markup("{lt}", "&lt;"), self.markup("{gt}", "&gt;"));
+                self.push(&format!("{lt}/{name}{gt}"));
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.pre {
+            let text = decode_entities(text, true, self.format);
+            self.text_raw(&text);
+        } else {
+            self.push(&decode_entities(text, false, self.format));
+        }
+    }
+
+    fn inline_tag(&mut self, tag: &str) {
+        if self.pre {
+            // {@code} in <pre> is the usual way to avoid escaping in code samples
+            if let Some(code) = tag
+                .strip_prefix("{@code")
+                .and_then(|code| code.strip_suffix('}'))
+            {
+                let code = code.strip_prefix([' ', '\n']).unwrap_or(code);
+                self.text_raw(code);
+                return;
+            }
+        }
+        self.push(tag);
+    }
+
+    fn text_raw(&mut self, text: &str) {
+        // like in html, a line break directly after <pre> is ignored
+        let buffer = &self.frames.last().unwrap().1;
+        let delimiter = format!("{}\n", self.listing_delimiter());
+        let text = if buffer.ends_with(&delimiter) {
+            text.strip_prefix('\n').unwrap_or(text)
+        } else {
+            text
+        };
+        self.push(text);
+    }
+
+    fn finish(mut self) -> String {
+        while self.frames.len() > 1 {
+            self.close();
+        }
+        while !self.tables.is_empty() {
+            self.finish_table();
+        }
+        if self.pre {
+            self.push(&format!("\n{}\n", self.listing_delimiter()));
+        }
+        tidy(&self.frames.pop().unwrap().1, self.format)
+    }
+}
+
+/// Renders a table as markdown table, which always starts with a header row
+fn markdown_table(table: &Table, columns: usize, has_header: bool) -> String {
+    let mut result = String::from("\n\n");
+    if !table.caption.is_empty() {
+        result.push_str(&format!("**{}**\n\n", table.caption));
+    }
+    let row = |cells: &[(bool, String)]| {
+        let cells: Vec<&str> = (0..columns)
+            .map(|index| {
+                cells
+                    .get(index)
+                    .map(|(_, cell)| cell.as_str())
+                    .unwrap_or("")
+            })
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut rows = table.rows.iter();
+    let header = match has_header {
+        true => rows.next().map(|cells| row(cells)),
+        false => None,
+    };
+    result.push_str(&header.unwrap_or_else(|| row(&[])));
+    result.push_str(&format!("|{}\n", " --- |".repeat(columns)));
+    for cells in rows {
+        result.push_str(&row(cells));
+    }
+    result.push('\n');
+    result
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words.join(" ")
+}
+
+/// Trims all lines outside of listings and removes superfluous blank lines. The indentation of
+/// nested markdown list items is kept.
+pub(super) fn tidy(text: &str, format: TextFormat) -> String {
+    let mut lines: Vec<&str> = vec![];
+    let mut listing = false;
+    for line in text.lines() {
+        let delimits = match format {
+            TextFormat::Asciidoc => line.trim() == "----",
+            TextFormat::Markdown => line.trim().starts_with("```"),
+        };
+        if delimits {
+            listing = !listing;
+            lines.push(line.trim());
+            continue;
+        }
+        if listing {
+            lines.push(line.trim_end());
+            continue;
+        }
+        let line = match format {
+            TextFormat::Markdown if is_markdown_item(line) => line.trim_end(),
+            _ => line.trim(),
+        };
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_owned()
+}
+
+/// Whether `line` is an item of a markdown list, like `- item` or `1. item`
+fn is_markdown_item(line: &str) -> bool {
+    let line = line.trim_start();
+    let marker = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    line.starts_with("- ") || (marker.len() < line.len() && marker.starts_with(". "))
+}
+
+/// Converts the html markup of a javadoc text into asciidoc. Inline tags are kept for a later
+/// conversion, apart from `{@code}` blocks within `<pre>` which end up in the listing.
+pub fn html_to_adoc(html: &str) -> String {
+    convert(html, TextFormat::Asciidoc)
+}
+
+/// Like [html_to_adoc], but converts into markdown
+pub fn html_to_markdown(html: &str) -> String {
+    convert(html, TextFormat::Markdown)
+}
+
+fn convert(html: &str, format: TextFormat) -> String {
+    let mut converter = Converter::new(format);
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => converter.text(text),
+            Token::InlineTag(tag) => converter.inline_tag(tag),
+            Token::Start { name, attributes } => converter.start(&name, &attributes),
+            Token::End { name } => converter.end(&name),
+        }
+    }
+    converter.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paragraphs() {
+        assert_eq!(
+            html_to_adoc("first\n<p>\nsecond<p>third</p>"),
+            "first\n\nsecond\n\nthird"
+        );
+    }
+
+    #[test]
+    fn inline_formatting() {
+        assert_eq!(
+            html_to_adoc("<b>bold</b>, <em>emphasis</em> and <code>code</code><br>next"),
+            "**bold**, __emphasis__ and ``code`` +\nnext"
+        );
+    }
+
+    #[test]
+    fn lists() {
+        let html = "<ul>\n<li>one\n<li>two <ol><li>nested</li></ol>\n</ul>";
+        assert_eq!(html_to_adoc(html), "* one\n* two\n.. nested");
+    }
+
+    #[test]
+    fn listings() {
+        let html = "Example:\n<pre>{@code\n  List<String> list = new ArrayList<>();\n}</pre>";
+        assert_eq!(
+            html_to_adoc(html),
+            "Example:\n\n----\n  List<String> list = new ArrayList<>();\n----"
+        );
+        let html = "<pre>\n  a &lt; b\n</pre>";
+        assert_eq!(html_to_adoc(html), "----\n  a < b\n----");
+    }
+
+    #[test]
+    fn links_and_anchors() {
+        assert_eq!(
+            html_to_adoc("see <a href=\"https://example.com\">the [docs]</a><a name=\"here\"></a>"),
+            "see link:https://example.com[the [docs\\]][[here]]"
+        );
+        let html = "<a href=\"https://example.com/x?a=1&amp;b=2\">query</a>";
+        assert_eq!(
+            html_to_adoc(html),
+            "link:https://example.com/x?a=1&b=2[query]"
+        );
+        assert_eq!(
+            html_to_markdown(html),
+            "[query](https://example.com/x?a=1&b=2)"
+        );
+    }
+
+    #[test]
+    fn headings_and_quotes() {
+        assert_eq!(
+            html_to_adoc("<h2>Usage</h2><blockquote>quoted</blockquote>"),
+            "[discrete]\n=== Usage\n\n____\nquoted\n____"
+        );
+    }
+
+    #[test]
+    fn tables() {
+        let html = "<table><caption>Modes</caption><tr><th>Mode<th>Meaning</tr>\
+                    <tr><td>r</td><td>read | only</td></tr></table>";
+        assert_eq!(
+            html_to_adoc(html),
+            ".Modes\n[cols=\"1,1\",options=\"header\"]\n|===\n|Mode\n|Meaning\n\n|r\n|read \\| only\n\n|==="
+        );
+    }
+
+    #[test]
+    fn description_lists() {
+        let html = "<dl><dt>key<dd>the key<dt>value</dt><dd>the value</dd></dl>";
+        assert_eq!(html_to_adoc(html), "key:: the key\nvalue:: the value");
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(
+            html_to_adoc("a &lt; b &amp;&amp; c&nbsp;&#8211; &unknown; x"),
+            "a {lt} b {amp}{amp} c{nbsp}– &unknown; x"
+        );
+    }
+
+    #[test]
+    fn keeps_inline_tags() {
+        assert_eq!(
+            html_to_adoc("use {@code <b>not bold</b>} here"),
+            "use {@code <b>not bold</b>} here"
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let html = "A <b>bold</b> <a href=\"https://example.com\">link</a>:\n\
+                    <ul><li>one<ol><li>nested</li></ol></ul>\n\
+                    <pre>a &lt; b</pre>\n\
+                    <table><tr><th>Mode<th>Meaning</tr><tr><td>r<td>read | only</tr></table>";
+        assert_eq!(
+            html_to_markdown(html),
+            "A **bold** [link](https://example.com):\n\n- one\n    1. nested\n\n```\na < b\n```\n\n\
+             | Mode | Meaning |\n| --- | --- |\n| r | read \\| only |"
+        );
+    }
+
+    #[test]
+    fn unknown_tags() {
+        assert_eq!(
+            html_to_adoc("<custom attr='x'>text</custom> and a < b"),
+            "{lt}custom attr=\"x\"{gt}text{lt}/custom{gt} and a < b"
+        );
+    }
+}
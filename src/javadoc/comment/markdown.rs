@@ -0,0 +1,452 @@
+//! Conversion of markdown documentation comments (JEP 467) into asciidoc, or into markdown with
+//! references to program elements resolved
+
+use std::ops::Range;
+
+use pulldown_cmark::{
+    BrokenLink, CodeBlockKind, CowStr, Event, HeadingLevel, LinkType, Options, Parser, Tag, TagEnd,
+};
+use tracing::trace;
+
+use crate::javadoc::{inline::monospace, TextFormat};
+
+use super::html::{html_to_adoc, tidy};
+
+/// Strips the `///` and the indentation common to all lines from a markdown documentation
+/// comment made of consecutive line comments
+pub fn strip_markdown_lines(source: &str) -> Vec<String> {
+    let lines: Vec<&str> = source
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix("///").unwrap_or(line).trim_end()
+        })
+        .collect();
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indentation..).unwrap_or("").to_owned())
+        .collect()
+}
+
+/// Whether the text of a reference link like `[String#length()]` names a program element
+fn is_reference(reference: &str) -> bool {
+    !reference.is_empty()
+        && reference.starts_with(|c: char| c.is_alphabetic() || c == '#')
+        && reference.chars().all(|c| {
+            c.is_alphanumeric() || "_$.#()[], ".contains(c)
+        })
+        // spaces are only allowed within the parameter list of methods
+        && reference
+            .split_once('(')
+            .map_or(!reference.contains(' '), |(name, _)| !name.contains(' '))
+}
+
+#[derive(Debug)]
+enum Frame {
+    Root,
+    Link { reference: String, plain: bool },
+    Heading(usize),
+    Cell,
+    Image(String),
+}
+
+#[derive(Debug)]
+struct Converter {
+    frames: Vec<(Frame, String)>,
+    /// open lists, `true` for ordered ones
+    lists: Vec<bool>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Converter {
+    fn push(&mut self, text: &str) {
+        self.frames.last_mut().unwrap().1.push_str(text);
+    }
+
+    /// Appends `line` on a line of its own
+    fn push_line(&mut self, line: &str) {
+        let buffer = &self.frames.last().unwrap().1;
+        if !buffer.is_empty() && !buffer.ends_with('\n') {
+            self.push("\n");
+        }
+        self.push(line);
+    }
+
+    fn open(&mut self, frame: Frame) {
+        self.frames.push((frame, String::new()));
+    }
+
+    fn close(&mut self) {
+        if self.frames.len() <= 1 {
+            return;
+        }
+        let (frame, content) = self.frames.pop().unwrap();
+        let content = content.trim();
+        match frame {
+            Frame::Root => (),
+            // program elements are linked through the inline tags, converted later on
+            Frame::Link { reference, plain } if is_reference(&reference) => {
+                if plain {
+                    self.push(&format!("{{@linkplain {reference} {content}}}"));
+                } else {
+                    self.push(&format!("{{@link {reference}}}"));
+                }
+            }
+            Frame::Link { reference, .. } => {
+                let label = if content.is_empty() {
+                    reference.clone()
+                } else {
+                    content.replace(']', "\\]")
+                };
+                match reference.strip_prefix('#') {
+                    Some(anchor) => self.push(&format!("<<{anchor},{label}>>")),
+                    None => self.push(&format!("link:{reference}[{label}]")),
+                }
+            }
+            Frame::Heading(level) => {
+                let marker = "=".repeat(level + 1);
+                self.push(&format!("\n\n[discrete]\n{marker} {content}\n\n"));
+            }
+            Frame::Cell => {
+                let cell = content.replace('|', "\\|");
+                if let Some(row) = self.rows.last_mut() {
+                    row.push(cell);
+                }
+            }
+            Frame::Image(src) => self.push(&format!("image:{src}[{content}]")),
+        }
+    }
+
+    /// Renders the collected rows, markdown tables always start with a header row
+    fn finish_table(&mut self) {
+        let rows: Vec<Vec<String>> = self.rows.drain(..).collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(1);
+        let cols = vec!["1"; columns].join(",");
+        let mut result = format!("\n\n[cols=\"{cols}\",options=\"header\"]\n|===\n");
+        for row in &rows {
+            for index in 0..columns {
+                let cell = row.get(index).map(String::as_str).unwrap_or("");
+                result.push_str(&format!("|{cell}\n"));
+            }
+            result.push('\n');
+        }
+        result.push_str("|===\n\n");
+        self.push(&result);
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Paragraph => {
+                // paragraphs of list items are kept on the line of the item
+                if self.lists.is_empty() {
+                    self.push("\n\n");
+                }
+            }
+            Tag::Heading { level, .. } => {
+                let level = match level {
+                    HeadingLevel::H1 => 1,
+                    HeadingLevel::H2 => 2,
+                    HeadingLevel::H3 => 3,
+                    HeadingLevel::H4 => 4,
+                    HeadingLevel::H5 => 5,
+                    HeadingLevel::H6 => 6,
+                };
+                self.open(Frame::Heading(level));
+            }
+            Tag::BlockQuote(_) => self.push("\n\n____\n"),
+            Tag::CodeBlock(kind) => match kind {
+                CodeBlockKind::Fenced(language) if !language.is_empty() => {
+                    self.push(&format!("\n\n[source,{language}]\n----\n"));
+                }
+                _ => self.push("\n\n----\n"),
+            },
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.push("\n\n");
+                }
+                self.lists.push(start.is_some());
+            }
+            Tag::Item => {
+                let ordered = self.lists.last().copied().unwrap_or(false);
+                let marker = if ordered { "." } else { "*" };
+                let marker = marker.repeat(self.lists.len().max(1));
+                self.push_line(&format!("{marker} "));
+            }
+            Tag::Table(_) => self.rows.clear(),
+            Tag::TableHead | Tag::TableRow => self.rows.push(vec![]),
+            Tag::TableCell => self.open(Frame::Cell),
+            Tag::Emphasis => self.push("__"),
+            Tag::Strong => self.push("**"),
+            Tag::Strikethrough => self.push("[.line-through]#"),
+            Tag::Superscript => self.push("^"),
+            Tag::Subscript => self.push("~"),
+            Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            } => {
+                // `[text][ref]` shows its text like {@linkplain}, `[ref]` the reference in code
+                let plain = !matches!(
+                    link_type,
+                    LinkType::ShortcutUnknown | LinkType::CollapsedUnknown
+                );
+                self.open(Frame::Link {
+                    reference: dest_url.to_string(),
+                    plain,
+                });
+            }
+            Tag::Image { dest_url, .. } => self.open(Frame::Image(dest_url.to_string())),
+            Tag::HtmlBlock
+            | Tag::FootnoteDefinition(_)
+            | Tag::DefinitionList
+            | Tag::DefinitionListTitle
+            | Tag::DefinitionListDefinition
+            | Tag::MetadataBlock(_) => (),
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                if self.lists.is_empty() {
+                    self.push("\n\n");
+                } else {
+                    self.push(" ");
+                }
+            }
+            TagEnd::Heading(_) | TagEnd::Link | TagEnd::Image | TagEnd::TableCell => self.close(),
+            TagEnd::BlockQuote(_) => self.push("\n____\n\n"),
+            TagEnd::CodeBlock => {
+                let buffer = &mut self.frames.last_mut().unwrap().1;
+                let trimmed = buffer.trim_end().len();
+                buffer.truncate(trimmed);
+                self.push("\n----\n\n");
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                self.push("\n");
+            }
+            TagEnd::Item => self.push("\n"),
+            TagEnd::Table => self.finish_table(),
+            TagEnd::Emphasis => self.push("__"),
+            TagEnd::Strong => self.push("**"),
+            TagEnd::Strikethrough => self.push("#"),
+            TagEnd::Superscript => self.push("^"),
+            TagEnd::Subscript => self.push("~"),
+            TagEnd::TableHead
+            | TagEnd::TableRow
+            | TagEnd::HtmlBlock
+            | TagEnd::FootnoteDefinition
+            | TagEnd::DefinitionList
+            | TagEnd::DefinitionListTitle
+            | TagEnd::DefinitionListDefinition
+            | TagEnd::MetadataBlock(_) => (),
+        }
+    }
+
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.push(&text),
+            Event::Code(code) => self.push(&monospace(&code, TextFormat::Asciidoc)),
+            Event::Html(html) | Event::InlineHtml(html) => {
+                trace!("Converting embedded html {html:?}");
+                self.push(&html_to_adoc(&html));
+            }
+            Event::SoftBreak => self.push("\n"),
+            Event::HardBreak => self.push(" +\n"),
+            Event::Rule => self.push("\n\n'''\n\n"),
+            Event::TaskListMarker(checked) => self.push(if checked { "[x] " } else { "[ ] " }),
+            Event::InlineMath(math) | Event::DisplayMath(math) => self.push(&math),
+            Event::FootnoteReference(name) => self.push(&name),
+        }
+    }
+}
+
+/// Converts the CommonMark text of a markdown documentation comment into asciidoc. Inline tags
+/// like `{@link ...}` are kept for a later conversion, references to program elements like
+/// `[List]` are turned into such tags.
+pub fn markdown_to_adoc(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let parser = Parser::new_with_broken_link_callback(
+        markdown,
+        options,
+        Some(|link: BrokenLink<'_>| resolve_reference(markdown, link)),
+    );
+    let mut converter = Converter {
+        frames: vec![(Frame::Root, String::new())],
+        lists: vec![],
+        rows: vec![],
+    };
+    for event in parser {
+        converter.event(event);
+    }
+    while converter.frames.len() > 1 {
+        converter.close();
+    }
+    tidy(&converter.frames.pop().unwrap().1, TextFormat::Asciidoc)
+}
+
+/// Text in brackets which isn't a reference to a program element, like `array[index]`, is kept as
+/// it is. Anything else is resolved to the reference itself.
+fn resolve_reference<'a>(markdown: &str, link: BrokenLink<'_>) -> Option<(CowStr<'a>, CowStr<'a>)> {
+    let indexing = markdown[..link.span.start]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == ']' || c == ')');
+    let reference = link.reference.to_string();
+    if indexing || !is_reference(&reference) {
+        return None;
+    }
+    Some((CowStr::from(reference), CowStr::from("")))
+}
+
+/// A link to a program element within a markdown comment
+struct ReferenceLink {
+    /// Position of the whole link in the comment
+    range: Range<usize>,
+    reference: String,
+    /// Whether the link has a label of its own, like `{@linkplain}`
+    plain: bool,
+    /// Position of the label in the comment, once known
+    label: Option<Range<usize>>,
+}
+
+/// Keeps the CommonMark text of a markdown documentation comment, only turning references to
+/// program elements like `[List]` into inline tags like `{@link List}`, converted later on
+pub fn markdown_references(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let parser = Parser::new_with_broken_link_callback(
+        markdown,
+        options,
+        Some(|link: BrokenLink<'_>| resolve_reference(markdown, link)),
+    );
+
+    let mut result = String::new();
+    let mut position = 0;
+    let mut link: Option<ReferenceLink> = None;
+    for (event, range) in parser.into_offset_iter() {
+        match (event, link.as_mut()) {
+            (Event::End(TagEnd::Link), Some(_)) => {
+                let ReferenceLink {
+                    range,
+                    reference,
+                    plain,
+                    label,
+                } = link.take().unwrap();
+                let label = label.map(|label| &markdown[label]).unwrap_or_default();
+                result.push_str(&markdown[position..range.start]);
+                if plain && !label.is_empty() {
+                    result.push_str(&format!("{{@linkplain {reference} {label}}}"));
+                } else {
+                    result.push_str(&format!("{{@link {reference}}}"));
+                }
+                position = range.end;
+            }
+            (_, Some(link)) => {
+                let start = link.label.as_ref().map_or(range.start, |label| label.start);
+                link.label = Some(start..range.end);
+            }
+            (
+                Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    ..
+                }),
+                None,
+            ) if is_reference(&dest_url) => {
+                let plain = !matches!(
+                    link_type,
+                    LinkType::ShortcutUnknown | LinkType::CollapsedUnknown
+                );
+                link = Some(ReferenceLink {
+                    range,
+                    reference: dest_url.to_string(),
+                    plain,
+                    label: None,
+                });
+            }
+            _ => (),
+        }
+    }
+    result.push_str(&markdown[position..]);
+    result.trim().to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_lines() {
+        let source = "///   Returns the\n  ///   value.\n  ///\n  ///     indented";
+        assert_eq!(
+            strip_markdown_lines(source),
+            vec!["Returns the", "value.", "", "  indented"]
+        );
+    }
+
+    #[test]
+    fn converts_inline_markup() {
+        assert_eq!(
+            markdown_to_adoc("Some *emphasis*, **strong** and `code` text"),
+            "Some __emphasis__, **strong** and `+code+` text"
+        );
+    }
+
+    #[test]
+    fn converts_headings_and_lists() {
+        let markdown = "# Usage\n\n- one\n- two\n  1. nested\n\nafter";
+        assert_eq!(
+            markdown_to_adoc(markdown),
+            "[discrete]\n== Usage\n\n* one\n* two\n.. nested\n\nafter"
+        );
+    }
+
+    #[test]
+    fn converts_code_blocks() {
+        let markdown = "Example:\n\n```java\nif (a < b) {\n    run();\n}\n```\n";
+        assert_eq!(
+            markdown_to_adoc(markdown),
+            "Example:\n\n[source,java]\n----\nif (a < b) {\n    run();\n}\n----"
+        );
+    }
+
+    #[test]
+    fn converts_reference_links() {
+        assert_eq!(
+            markdown_to_adoc("See [String#length()] and [a list][java.util.List]."),
+            "See {@link String#length()} and {@linkplain java.util.List a list}."
+        );
+        assert_eq!(
+            markdown_to_adoc("An [example](https://example.com), array[index]"),
+            "An link:https://example.com[example], array[index]"
+        );
+    }
+
+    #[test]
+    fn keeps_markdown_with_references() {
+        assert_eq!(
+            markdown_references(
+                "See *the* [String#length()], [a `list`][java.util.List] and array[index].\n\n- [ok](https://example.com)"
+            ),
+            "See *the* {@link String#length()}, {@linkplain java.util.List a `list`} and array[index].\n\n- [ok](https://example.com)"
+        );
+    }
+
+    #[test]
+    fn converts_tables() {
+        let markdown = "| Mode | Meaning |\n|------|---------|\n| r | read |";
+        assert_eq!(
+            markdown_to_adoc(markdown),
+            "[cols=\"1,1\",options=\"header\"]\n|===\n|Mode\n|Meaning\n\n|r\n|read\n\n|==="
+        );
+    }
+}
@@ -2,7 +2,14 @@ use tracing::debug;
 use tree_sitter::Node;
 
 use super::{
-    comment::{find_block_comment, BlockComment},
+    anchor::{render_anchor, tagged_region},
+    comment::{find_documentation, BlockComment},
+    declaration::{normalize_whitespace, render_declaration},
+    prefix_hashes,
+    signature::{
+        parameters, render_parameters, render_returns, render_throws, render_type_parameters,
+        SIGNATURE_TAGS,
+    },
     FileContext, JavaDocable,
 };
 
@@ -18,7 +25,7 @@ impl<'a> JavaDocable<'a> for Constructor<'a> {
     where
         Self: Sized,
     {
-        let comment = find_block_comment(node, ctx);
+        let comment = find_documentation(node, ctx);
         if let Some(comment) = comment {
             Some(Self {
                 comment,
@@ -39,7 +46,7 @@ impl<'a> JavaDocable<'a> for Constructor<'a> {
         self.context
     }
 
-    fn get_comment(&self) -> &'a BlockComment {
+    fn get_comment(&self) -> &BlockComment<'a> {
         &self.comment
     }
 
@@ -47,9 +54,36 @@ impl<'a> JavaDocable<'a> for Constructor<'a> {
         let node = self.get_node();
         let ctx = self.get_context();
         let name = node.child_by_field_name("name").unwrap();
-        let params = node.child_by_field_name("parameters").unwrap();
         let name = ctx.source_for_range(&name.range());
-        let params = ctx.source_for_range(&params.range());
-        format!("{name} {params}")
+        // compact canonical constructors of records have no parameter list
+        if node.child_by_field_name("parameters").is_none() {
+            return name.to_owned();
+        }
+        let paramtypes: Vec<String> = parameters(node, ctx)
+            .iter()
+            .map(|param| normalize_whitespace(&param.paramtype))
+            .collect();
+        let paramtypes = paramtypes.join(", ");
+        format!("{name}({paramtypes})")
+    }
+
+    fn render(&'a self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let name = self.get_name();
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes}= {name}");
+        let node = self.get_node();
+        let ctx = self.get_context();
+        let comment = self.get_comment();
+        let javadoc = comment.rendered_javadoc();
+        let declaration = render_declaration(node, ctx);
+        let content = comment.render_without(&SIGNATURE_TAGS);
+        let type_parameters = render_type_parameters(node, ctx, &javadoc);
+        let parameters = render_parameters(node, ctx, &javadoc);
+        let returns = render_returns(node, ctx, &javadoc);
+        let throws = render_throws(node, ctx, &javadoc);
+        let tables = format!("{type_parameters}{parameters}{returns}{throws}");
+        let rendered = format!("\n\n{headline}\n\n{declaration}{content}{tables}");
+        tagged_region(self.get_node(), self.get_context(), rendered)
     }
 }
@@ -0,0 +1,185 @@
+//! Declarations of types and members as written in the source, rendered as java listings above
+//! their documentation.
+
+use tree_sitter::Node;
+
+use super::FileContext;
+
+/// Renders the declaration of a type or member as `[source,java]` listing
+pub fn render_declaration(node: Node<'_>, ctx: &FileContext) -> String {
+    let declaration = declaration(node, ctx);
+    format!("[source,java]\n----\n{declaration}\n----\n\n")
+}
+
+/// The declaration of a type or member without its body, comments and trailing semicolon.
+/// Annotations are put on lines of their own, everything else like modifiers, type parameters,
+/// `extends`, `implements`, `permits` and `throws` clauses follows on a single line.
+pub fn declaration(node: Node<'_>, ctx: &FileContext) -> String {
+    let end = match node.child_by_field_name("body") {
+        Some(body) => body.start_byte(),
+        None => node.end_byte(),
+    };
+    let Modifiers {
+        mut annotations,
+        mut keywords,
+        end: start,
+    } = split_modifiers(node, ctx);
+    keywords.push(source_without_comments(node, start, end, ctx));
+
+    let signature = keywords.join(" ");
+    let signature = signature.trim().trim_end_matches(';').trim_end();
+    annotations.push(signature.to_owned());
+    annotations.join("\n")
+}
+
+/// Annotations of a declaration as written in the source, like `@SuppressWarnings("unchecked")`
+pub fn annotations(node: Node<'_>, ctx: &FileContext) -> Vec<String> {
+    split_modifiers(node, ctx).annotations
+}
+
+/// Modifier keywords of a declaration, like `public` or `static`
+pub fn modifiers(node: Node<'_>, ctx: &FileContext) -> Vec<String> {
+    split_modifiers(node, ctx).keywords
+}
+
+/// The modifiers of a declaration, split into annotations and keywords
+struct Modifiers {
+    annotations: Vec<String>,
+    keywords: Vec<String>,
+    /// Byte offset the rest of the declaration starts at
+    end: usize,
+}
+
+fn split_modifiers(node: Node<'_>, ctx: &FileContext) -> Modifiers {
+    let mut annotations: Vec<String> = vec![];
+    let mut keywords: Vec<String> = vec![];
+    let mut cursor = node.walk();
+    let modifiers = node
+        .children(&mut cursor)
+        .find(|child| child.grammar_name() == "modifiers");
+    let end = match modifiers {
+        Some(modifiers) => {
+            let mut cursor = modifiers.walk();
+            for modifier in modifiers.children(&mut cursor) {
+                let text = source_without_comments(
+                    modifier,
+                    modifier.start_byte(),
+                    modifier.end_byte(),
+                    ctx,
+                );
+                match modifier.grammar_name() {
+                    "annotation" | "marker_annotation" => annotations.push(text),
+                    "line_comment" | "block_comment" => (),
+                    _ => keywords.push(text),
+                }
+            }
+            modifiers.end_byte()
+        }
+        None => node.start_byte(),
+    };
+    Modifiers {
+        annotations,
+        keywords,
+        end,
+    }
+}
+
+/// Collapses all whitespace, including line breaks, into single spaces
+pub fn normalize_whitespace(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words.join(" ")
+}
+
+/// Source between the byte offsets `start` and `end` within `node`, with all comments removed and
+/// whitespace normalized
+fn source_without_comments(node: Node<'_>, start: usize, end: usize, ctx: &FileContext) -> String {
+    let mut comments: Vec<(usize, usize)> = vec![];
+    collect_comments(node, &mut comments);
+
+    let mut text = String::new();
+    let mut position = start;
+    for (comment_start, comment_end) in comments {
+        if comment_end <= position || comment_start >= end {
+            continue;
+        }
+        text.push_str(&ctx.source[position..comment_start]);
+        text.push(' ');
+        position = comment_end;
+    }
+    if position < end {
+        text.push_str(&ctx.source[position..end]);
+    }
+    normalize_whitespace(&text)
+}
+
+/// Byte ranges of all comments within `node`, in order of appearance
+fn collect_comments(node: Node<'_>, comments: &mut Vec<(usize, usize)>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.grammar_name() {
+            "line_comment" | "block_comment" => {
+                comments.push((child.start_byte(), child.end_byte()))
+            }
+            _ => collect_comments(child, comments),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::parser::parse_string;
+
+    use super::*;
+
+    const INPUT: &str = "
+@Deprecated
+@SuppressWarnings(\"unchecked\")
+public final class Box<T extends Comparable<T>>
+        extends Base implements Comparable<Box<T>>, /* why not */ Cloneable {
+
+    @Override
+    public   <R> List<R> map(Function<? super T, R> mapper, // the mapper
+                             int limit) throws IOException,
+            IllegalStateException {
+        return null;
+    }
+
+    public static final String NAME = \"box\";
+}";
+
+    fn find<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+        if node.grammar_name() == kind {
+            return Some(node);
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        children.into_iter().find_map(|child| find(child, kind))
+    }
+
+    #[test]
+    fn renders_declarations() {
+        let tree = parse_string(INPUT).unwrap();
+        let ctx = FileContext::from_str(INPUT).unwrap();
+        let root = tree.root_node();
+
+        let class = find(root, "class_declaration").unwrap();
+        assert_eq!(
+            declaration(class, &ctx),
+            "@Deprecated\n@SuppressWarnings(\"unchecked\")\npublic final class Box<T extends Comparable<T>> extends Base implements Comparable<Box<T>>, Cloneable"
+        );
+
+        let method = find(root, "method_declaration").unwrap();
+        assert_eq!(
+            declaration(method, &ctx),
+            "@Override\npublic <R> List<R> map(Function<? super T, R> mapper, int limit) throws IOException, IllegalStateException"
+        );
+
+        let field = find(root, "field_declaration").unwrap();
+        assert_eq!(
+            declaration(field, &ctx),
+            "public static final String NAME = \"box\""
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use tracing::debug;
 use tree_sitter::Node;
 
-use crate::javadoc::comment::find_block_comment;
+use crate::javadoc::comment::find_documentation;
 
 use super::{comment::BlockComment, FileContext, JavaDocable};
 
@@ -17,7 +17,7 @@ impl<'a> JavaDocable<'a> for Field<'a> {
     where
         Self: Sized,
     {
-        let comment = find_block_comment(node, ctx);
+        let comment = find_documentation(node, ctx);
         if let Some(comment) = comment {
             Some(Self {
                 comment,
@@ -41,14 +41,12 @@ impl<'a> JavaDocable<'a> for Field<'a> {
         let node = self.get_node();
         let ctx = self.get_context();
         let declarator = node.child_by_field_name("declarator").unwrap();
-        let nodetype = node.child_by_field_name("type").unwrap();
         let name = declarator.child_by_field_name("name").unwrap();
-        let nodetype = ctx.source_for_range(&nodetype.range());
         let name = ctx.source_for_range(&name.range());
-        format!("{nodetype} {name}")
+        name.to_owned()
     }
 
-    fn get_comment(&self) -> &'a BlockComment {
+    fn get_comment(&self) -> &BlockComment<'a> {
         &self.comment
     }
 }
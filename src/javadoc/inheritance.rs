@@ -0,0 +1,648 @@
+//! Inheritance of documentation from overridden methods, following the algorithm of the javadoc
+//! tool: directly implemented interfaces are searched first, then their superinterfaces and
+//! finally the superclass chain.
+
+use std::collections::{HashMap, HashSet};
+
+use tree_sitter::Node;
+
+use super::{
+    anchor::{erase_type, erased_parameters},
+    comment::find_block_comment,
+    local_type_name,
+    signature::{parameters, same_type, thrown_types},
+    symbols::SymbolTable,
+    tags::{BlockTag, Javadoc},
+    FileContext, TYPE_DECLARATIONS,
+};
+
+const INHERIT_DOC: &str = "{@inheritDoc}";
+
+/// How far up the hierarchy documentation is searched, guarding against cyclic declarations
+const MAX_DEPTH: usize = 32;
+
+/// The supertypes of a type and the documentation of its methods, which subtypes may inherit
+#[derive(Debug, Clone, Default)]
+pub struct TypeHierarchy {
+    pub superclass: Option<String>,
+    /// Implemented interfaces, or extended ones for interfaces, in declaration order
+    pub interfaces: Vec<String>,
+    /// Names of the type parameters of the type itself
+    pub type_parameters: Vec<String>,
+    /// Type arguments the supertypes are extended or implemented with, by supertype
+    pub type_arguments: HashMap<String, Vec<TypeArgument>>,
+    pub methods: Vec<InheritableMethod>,
+}
+
+/// A type argument of a supertype, like `String` in `implements Comparable<String>`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeArgument {
+    /// An erased and qualified type, like in anchors
+    Type(String),
+    /// A type parameter of the extending type, bound by its own subtypes
+    Variable(String),
+}
+
+/// A documented method, as far as needed to inherit its documentation
+#[derive(Debug, Clone)]
+pub struct InheritableMethod {
+    pub name: String,
+    /// Erased parameter types, like in anchors
+    pub paramtypes: Vec<String>,
+    /// Type parameter of the declaring type each parameter is declared as, if any
+    pub variables: Vec<Option<String>>,
+    pub paramnames: Vec<String>,
+    /// Documentation with the markup already converted, but inline tags left as they are
+    pub javadoc: Javadoc,
+}
+
+impl TypeHierarchy {
+    /// Finds the method overridden by `name(paramtypes)`, with the type parameters of this type
+    /// bound to `arguments`. Unbound type parameters are erased to their bounds.
+    fn find_method(
+        &self,
+        name: &str,
+        paramtypes: &[String],
+        arguments: &[Option<String>],
+    ) -> Option<&InheritableMethod> {
+        self.methods.iter().find(|method| {
+            method.name == name
+                && method.paramtypes.len() == paramtypes.len()
+                && method
+                    .paramtypes
+                    .iter()
+                    .zip(&method.variables)
+                    .zip(paramtypes)
+                    .all(|((erased, variable), paramtype)| {
+                        let bound = variable
+                            .as_ref()
+                            .and_then(|variable| self.bind(variable, arguments));
+                        match bound {
+                            // arrays of type variables keep their dimensions
+                            Some(bound) => {
+                                let dimensions = erased.matches(":A").count();
+                                *paramtype == format!("{bound}{}", ":A".repeat(dimensions))
+                            }
+                            None => erased == paramtype,
+                        }
+                    })
+        })
+    }
+
+    /// The type bound to the type parameter `variable` of this type
+    fn bind<'a>(&self, variable: &str, arguments: &'a [Option<String>]) -> Option<&'a str> {
+        let position = self
+            .type_parameters
+            .iter()
+            .position(|parameter| parameter == variable)?;
+        arguments.get(position)?.as_deref()
+    }
+}
+
+/// Collects the hierarchy of all types declared within `node`, including undocumented ones
+pub fn collect_hierarchy(node: Node<'_>, ctx: &FileContext) -> Vec<(String, TypeHierarchy)> {
+    let mut result: Vec<(String, TypeHierarchy)> = vec![];
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if TYPE_DECLARATIONS.contains(&child.grammar_name()) {
+            if let Some(local_type) = local_type_name(child, ctx) {
+                result.push((
+                    ctx.fqn(&local_type),
+                    type_hierarchy(child, &local_type, ctx),
+                ));
+            }
+        }
+        result.extend(collect_hierarchy(child, ctx));
+    }
+    result
+}
+
+fn type_hierarchy<'a>(node: Node<'a>, local_type: &str, ctx: &FileContext) -> TypeHierarchy {
+    // supertypes are named as seen from the declaring scope
+    let enclosing = local_type.rsplit_once('.').map(|(outer, _)| outer);
+    let qualify = |node: Node<'_>| {
+        let name = ctx.source_for_range(&node.range());
+        erase_type(name, |t| ctx.qualify_type(t, enclosing))
+    };
+    let type_list = |node: Option<Node<'a>>| -> Vec<Node<'a>> {
+        let Some(list) = node.and_then(|node| node.named_child(0)) else {
+            return vec![];
+        };
+        let mut cursor = list.walk();
+        let types = list.named_children(&mut cursor).collect();
+        types
+    };
+
+    let superclass = node
+        .child_by_field_name("superclass")
+        .and_then(|superclass| superclass.named_child(0));
+    let interfaces = match node.grammar_name() {
+        "interface_declaration" => {
+            let mut cursor = node.walk();
+            let extends = node
+                .named_children(&mut cursor)
+                .find(|child| child.grammar_name() == "extends_interfaces");
+            type_list(extends)
+        }
+        _ => type_list(node.child_by_field_name("interfaces")),
+    };
+    let type_parameters = type_parameter_names(node, ctx);
+    let type_arguments = superclass
+        .iter()
+        .chain(&interfaces)
+        .map(|supertype| {
+            let arguments = type_arguments(*supertype, &type_parameters, qualify, ctx);
+            (qualify(*supertype), arguments)
+        })
+        .collect();
+    let methods = node
+        .child_by_field_name("body")
+        .map(|body| documented_methods(body, &type_parameters, ctx))
+        .unwrap_or_default();
+    TypeHierarchy {
+        superclass: superclass.map(qualify),
+        interfaces: interfaces.into_iter().map(qualify).collect(),
+        type_parameters,
+        type_arguments,
+        methods,
+    }
+}
+
+/// Names of the type parameters declared by a type or method
+fn type_parameter_names(node: Node<'_>, ctx: &FileContext) -> Vec<String> {
+    let Some(params) = node.child_by_field_name("type_parameters") else {
+        return vec![];
+    };
+    let mut cursor = params.walk();
+    let names = params
+        .named_children(&mut cursor)
+        .filter_map(|param| {
+            let mut cursor = param.walk();
+            let name = param
+                .named_children(&mut cursor)
+                .find(|child| matches!(child.kind(), "identifier" | "type_identifier"))?;
+            Some(ctx.source_for_range(&name.range()).to_owned())
+        })
+        .collect();
+    names
+}
+
+/// Type arguments of a supertype like `Comparable<T>`, telling type parameters of the extending
+/// type apart from actual types
+fn type_arguments(
+    supertype: Node<'_>,
+    type_parameters: &[String],
+    qualify: impl Fn(Node<'_>) -> String,
+    ctx: &FileContext,
+) -> Vec<TypeArgument> {
+    let mut cursor = supertype.walk();
+    let Some(arguments) = supertype
+        .named_children(&mut cursor)
+        .find(|child| child.grammar_name() == "type_arguments")
+    else {
+        return vec![];
+    };
+    let mut cursor = arguments.walk();
+    let arguments = arguments
+        .named_children(&mut cursor)
+        .map(|argument| {
+            let name = ctx.source_for_range(&argument.range());
+            if type_parameters.iter().any(|parameter| parameter == name) {
+                TypeArgument::Variable(name.to_owned())
+            } else {
+                TypeArgument::Type(qualify(argument))
+            }
+        })
+        .collect();
+    arguments
+}
+
+fn documented_methods(
+    body: Node<'_>,
+    type_parameters: &[String],
+    ctx: &FileContext,
+) -> Vec<InheritableMethod> {
+    let mut methods: Vec<InheritableMethod> = vec![];
+    let mut cursor = body.walk();
+    for child in body.named_children(&mut cursor) {
+        match child.grammar_name() {
+            "method_declaration" => {
+                let Some(comment) = find_block_comment(child, ctx) else {
+                    continue;
+                };
+                let Some(name) = child.child_by_field_name("name") else {
+                    continue;
+                };
+                let params = parameters(child, ctx);
+                // type parameters of the method shadow those of the type
+                let shadowed = type_parameter_names(child, ctx);
+                let variables = params
+                    .iter()
+                    .map(|param| {
+                        let erased = erase_type(&param.paramtype, |t| t.to_owned());
+                        let base = erased.trim_end_matches(":A");
+                        let declared = type_parameters.iter().any(|t| t == base)
+                            && !shadowed.iter().any(|t| t == base);
+                        declared.then(|| base.to_owned())
+                    })
+                    .collect();
+                methods.push(InheritableMethod {
+                    name: ctx.source_for_range(&name.range()).to_owned(),
+                    paramtypes: erased_parameters(child, ctx),
+                    variables,
+                    paramnames: params.into_iter().map(|param| param.name).collect(),
+                    javadoc: comment.converted_javadoc(),
+                });
+            }
+            "enum_body_declarations" => {
+                methods.extend(documented_methods(child, type_parameters, ctx))
+            }
+            _ => (),
+        }
+    }
+    methods
+}
+
+/// A part of a method comment which is inherited on its own
+#[derive(Debug)]
+enum Part {
+    Description,
+    /// `@param` of the parameter at that position, as names may differ between overrides
+    Param(usize),
+    Return,
+    Throws(String),
+}
+
+fn part_of(method: &InheritableMethod, part: &Part) -> Option<String> {
+    let javadoc = &method.javadoc;
+    match part {
+        Part::Description => {
+            Some(javadoc.description.clone()).filter(|description| !description.is_empty())
+        }
+        Part::Param(index) => javadoc
+            .param(method.paramnames.get(*index)?)
+            .map(|description| description.to_owned()),
+        Part::Return => javadoc.returns().map(|content| content.to_owned()),
+        Part::Throws(exception) => javadoc
+            .throws()
+            .into_iter()
+            .find(|(documented, _)| same_type(documented, exception))
+            .map(|(_, description)| description.to_owned()),
+    }
+}
+
+/// Supertypes of `fqn` in the order their documentation is searched, together with the types
+/// bound to their type parameters as far as known. `arguments` are bound to the type parameters of
+/// `fqn` itself.
+fn inheritance_order(
+    symbols: &SymbolTable,
+    fqn: &str,
+    arguments: &[Option<String>],
+    depth: usize,
+) -> Vec<(String, Vec<Option<String>>)> {
+    let Some(hierarchy) = symbols.hierarchy(fqn) else {
+        return vec![];
+    };
+    if depth > MAX_DEPTH {
+        return vec![];
+    }
+    let bound = |supertype: &String| -> (String, Vec<Option<String>>) {
+        let arguments = hierarchy
+            .type_arguments
+            .get(supertype)
+            .map(|supertype_arguments| {
+                supertype_arguments
+                    .iter()
+                    .map(|argument| match argument {
+                        TypeArgument::Type(t) => Some(t.clone()),
+                        TypeArgument::Variable(variable) => {
+                            hierarchy.bind(variable, arguments).map(str::to_owned)
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        (supertype.clone(), arguments)
+    };
+    let interfaces: Vec<(String, Vec<Option<String>>)> =
+        hierarchy.interfaces.iter().map(bound).collect();
+    let mut order = interfaces.clone();
+    for (interface, arguments) in &interfaces {
+        order.extend(inheritance_order(symbols, interface, arguments, depth + 1));
+    }
+    if let Some(superclass) = &hierarchy.superclass {
+        let (superclass, arguments) = bound(superclass);
+        let supertypes = inheritance_order(symbols, &superclass, &arguments, depth + 1);
+        order.push((superclass, arguments));
+        order.extend(supertypes);
+    }
+    let mut seen: HashSet<String> = HashSet::new();
+    order.retain(|(supertype, _)| seen.insert(supertype.clone()));
+    order
+}
+
+/// Searches the supertypes of `fqn` for the documentation of `part` of the method
+/// `name(paramtypes)`, resolving `{@inheritDoc}` within the found text as well
+fn inherited_part(
+    symbols: &SymbolTable,
+    fqn: &str,
+    method: (&str, &[String]),
+    part: &Part,
+    depth: usize,
+) -> Option<String> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let (name, paramtypes) = method;
+    for (supertype, arguments) in inheritance_order(symbols, fqn, &[], 0) {
+        let Some(overridden) = symbols
+            .hierarchy(&supertype)
+            .and_then(|hierarchy| hierarchy.find_method(name, paramtypes, &arguments))
+        else {
+            continue;
+        };
+        let Some(text) = part_of(overridden, part) else {
+            continue;
+        };
+        if !text.contains(INHERIT_DOC) {
+            return Some(text);
+        }
+        let method = (name, overridden.paramtypes.as_slice());
+        let inherited =
+            inherited_part(symbols, &supertype, method, part, depth + 1).unwrap_or_default();
+        return Some(text.replace(INHERIT_DOC, &inherited));
+    }
+    None
+}
+
+/// Fills `{@inheritDoc}` as well as a missing description, `@param`, `@return` and `@throws`
+/// of a method from the methods it overrides. Anything but methods is returned unchanged.
+pub fn inherit_javadoc(javadoc: Javadoc, declaration: Node<'_>, ctx: &FileContext) -> Javadoc {
+    if declaration.grammar_name() != "method_declaration" {
+        return javadoc;
+    }
+    let Some(links) = ctx.links() else {
+        return javadoc;
+    };
+    let Some(local_type) = local_type_name(declaration, ctx) else {
+        return javadoc;
+    };
+    let Some(name) = declaration.child_by_field_name("name") else {
+        return javadoc;
+    };
+    let fqn = ctx.fqn(&local_type);
+    let name = ctx.source_for_range(&name.range());
+    let paramtypes = erased_parameters(declaration, ctx);
+    let inherited =
+        |part: Part| inherited_part(&links.symbols, &fqn, (name, &paramtypes), &part, 0);
+    let fill = |text: &str, part: Part| {
+        if text.contains(INHERIT_DOC) {
+            text.replace(INHERIT_DOC, &inherited(part).unwrap_or_default())
+        } else {
+            text.to_owned()
+        }
+    };
+
+    let mut javadoc = javadoc;
+    javadoc.description = if javadoc.description.is_empty() {
+        inherited(Part::Description).unwrap_or_default()
+    } else {
+        fill(&javadoc.description, Part::Description)
+    };
+    let paramnames: Vec<String> = parameters(declaration, ctx)
+        .into_iter()
+        .map(|param| param.name)
+        .collect();
+    for tag in javadoc.tags.iter_mut() {
+        match tag {
+            BlockTag::Param { name, description } => {
+                if let Some(index) = paramnames.iter().position(|param| param == name) {
+                    *description = fill(description, Part::Param(index));
+                }
+            }
+            BlockTag::Return(content) => *content = fill(content, Part::Return),
+            BlockTag::Throws {
+                exception,
+                description,
+            } => *description = fill(description, Part::Throws(exception.clone())),
+            _ => (),
+        }
+    }
+
+    for (index, name) in paramnames.iter().enumerate() {
+        if javadoc.param(name).is_some() {
+            continue;
+        }
+        if let Some(description) = inherited(Part::Param(index)) {
+            javadoc.tags.push(BlockTag::Param {
+                name: name.clone(),
+                description,
+            });
+        }
+    }
+    let returns_value = declaration
+        .child_by_field_name("type")
+        .is_some_and(|returntype| ctx.source_for_range(&returntype.range()) != "void");
+    if returns_value && javadoc.returns().is_none() {
+        if let Some(content) = inherited(Part::Return) {
+            javadoc.tags.push(BlockTag::Return(content));
+        }
+    }
+    // only exceptions of the throws clause are inherited, like the javadoc tool does
+    for exception in thrown_types(declaration, ctx) {
+        let documented = javadoc
+            .throws()
+            .iter()
+            .any(|(documented, _)| same_type(documented, &exception));
+        if documented {
+            continue;
+        }
+        if let Some(description) = inherited(Part::Throws(exception.clone())) {
+            javadoc.tags.push(BlockTag::Throws {
+                exception,
+                description,
+            });
+        }
+    }
+    javadoc
+}
+
+/// Whether an undocumented method inherits any documentation
+pub fn inherits_javadoc(declaration: Node<'_>, ctx: &FileContext) -> bool {
+    inherit_javadoc(Javadoc::default(), declaration, ctx) != Javadoc::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr, sync::Arc};
+
+    use crate::parser::parse_string;
+
+    use super::*;
+
+    const SUPER: &str = "
+package com.acme;
+
+interface Shape {
+  /**
+   * Computes the area.
+   * @param scale the scale
+   * @return the area
+   * @throws IllegalStateException when broken
+   */
+  double area(double scale) throws IllegalStateException;
+}";
+
+    const SUB: &str = "
+package com.acme;
+
+class Circle extends Base implements Shape {
+  double area(double factor) throws IllegalStateException { return 1; }
+
+  /**
+   * {@inheritDoc} For circles.
+   * @return {@inheritDoc}, rounded
+   */
+  double area(double factor) { return 1; }
+
+  void other() {}
+}";
+
+    const GENERIC_SUPER: &str = "
+package com.acme;
+
+class Base<T> {
+  /**
+   * Adds an int.
+   * @param value the int
+   */
+  void add(int value) {}
+
+  /**
+   * Puts an element.
+   * @param element the element
+   */
+  void put(T element) {}
+
+  /**
+   * Puts all elements.
+   * @param elements the elements
+   */
+  void putAll(T[] elements) {}
+}
+
+class Middle<E> extends Base<E> {}";
+
+    const GENERIC_SUB: &str = "
+package com.acme;
+
+class Impl extends Base<String> {
+  void add(String value) {}
+
+  void put(String element) {}
+
+  void putAll(String[] elements) {}
+
+  void put(Integer element) {}
+}
+
+class Leaf extends Middle<Integer> {
+  void put(Integer element) {}
+
+  void put(String element) {}
+}";
+
+    fn with_methods(f: impl Fn(&[Node<'_>], &FileContext)) {
+        with_sources(&[SUPER, SUB], f);
+    }
+
+    /// Calls `f` with the methods of all types of the last source
+    fn with_sources(sources: &[&str], f: impl Fn(&[Node<'_>], &FileContext)) {
+        let mut symbols = SymbolTable::default();
+        for source in sources {
+            let tree = parse_string(source).unwrap();
+            let ctx = FileContext::from_str(source).unwrap();
+            for (fqn, hierarchy) in collect_hierarchy(tree.root_node(), &ctx) {
+                symbols.insert_hierarchy(fqn, hierarchy);
+            }
+        }
+        let source = sources.last().unwrap();
+        let tree = parse_string(source).unwrap();
+        let ctx = FileContext::from_str(source).unwrap().with_links(
+            Arc::new(symbols),
+            PathBuf::from("Sub.adoc"),
+            None,
+        );
+        let mut cursor = tree.root_node().walk();
+        let mut methods: Vec<Node> = vec![];
+        for class in tree.root_node().named_children(&mut cursor) {
+            let Some(body) = class.child_by_field_name("body") else {
+                continue;
+            };
+            let mut cursor = body.walk();
+            methods.extend(
+                body.named_children(&mut cursor)
+                    .filter(|child| child.grammar_name() == "method_declaration"),
+            );
+        }
+        f(&methods, &ctx);
+    }
+
+    #[test]
+    fn collects_hierarchy() {
+        let tree = parse_string(SUB).unwrap();
+        let ctx = FileContext::from_str(SUB).unwrap();
+        let hierarchy = collect_hierarchy(tree.root_node(), &ctx);
+        assert_eq!(hierarchy.len(), 1);
+        let (fqn, hierarchy) = &hierarchy[0];
+        assert_eq!(fqn, "com.acme.Circle");
+        assert_eq!(hierarchy.superclass.as_deref(), Some("com.acme.Base"));
+        assert_eq!(hierarchy.interfaces, vec!["com.acme.Shape".to_owned()]);
+        assert_eq!(hierarchy.methods.len(), 1);
+    }
+
+    #[test]
+    fn inherits_missing_documentation() {
+        with_methods(|methods, ctx| {
+            let javadoc = inherit_javadoc(Javadoc::default(), methods[0], ctx);
+            assert_eq!(javadoc.description, "Computes the area.");
+            assert_eq!(javadoc.param("factor"), Some("the scale"));
+            assert_eq!(javadoc.returns(), Some("the area"));
+            assert_eq!(
+                javadoc.throws(),
+                vec![("IllegalStateException", "when broken")]
+            );
+            assert!(!inherits_javadoc(methods[2], ctx));
+        });
+    }
+
+    #[test]
+    fn resolves_inherit_doc() {
+        with_methods(|methods, ctx| {
+            let comment = find_block_comment(methods[1], ctx).unwrap();
+            let javadoc = inherit_javadoc(comment.converted_javadoc(), methods[1], ctx);
+            assert_eq!(javadoc.description, "Computes the area. For circles.");
+            assert_eq!(javadoc.returns(), Some("the area, rounded"));
+            // undeclared exceptions are not inherited
+            assert!(javadoc.throws().is_empty());
+        });
+    }
+
+    #[test]
+    fn inherits_only_from_overridden_methods() {
+        with_sources(&[GENERIC_SUPER, GENERIC_SUB], |methods, ctx| {
+            // same name and arity, but no override
+            assert!(!inherits_javadoc(methods[0], ctx));
+            let javadoc = inherit_javadoc(Javadoc::default(), methods[1], ctx);
+            assert_eq!(javadoc.description, "Puts an element.");
+            assert_eq!(javadoc.param("element"), Some("the element"));
+            let javadoc = inherit_javadoc(Javadoc::default(), methods[2], ctx);
+            assert_eq!(javadoc.description, "Puts all elements.");
+            assert!(!inherits_javadoc(methods[3], ctx));
+            // type arguments are passed through intermediate types
+            let javadoc = inherit_javadoc(Javadoc::default(), methods[4], ctx);
+            assert_eq!(javadoc.description, "Puts an element.");
+            assert!(!inherits_javadoc(methods[5], ctx));
+        });
+    }
+}
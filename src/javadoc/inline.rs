@@ -0,0 +1,355 @@
+use tracing::{debug, trace};
+use tree_sitter::Node;
+
+use crate::output::xref_target;
+
+use super::{
+    anchor::{erase_type, field_anchor, method_anchor, type_anchor},
+    local_type_name,
+    snippet::render_snippet,
+    symbols::{field_value, MemberSymbol, TypeSymbol},
+    FileContext, TextFormat,
+};
+
+/// Converts the inline tags like `{@link ...}` within a piece of javadoc text. `scope` is the
+/// documented declaration, used to resolve references relative to it.
+pub fn convert_inline_tags(text: &str, scope: Option<Node<'_>>, ctx: &FileContext) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{@") {
+        result.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        let Some(end) = closing_brace(tag) else {
+            trace!("Unclosed inline tag {tag:?}");
+            result.push_str(tag);
+            return result;
+        };
+        let inner = &tag[2..end];
+        let (name, raw_content) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+        let content = raw_content.trim();
+        let format = ctx.text_format();
+        let converted = match name {
+            "link" => render_link(content, false, scope, ctx),
+            "linkplain" => render_link(content, true, scope, ctx),
+            "code" => monospace(content, format),
+            "literal" => passthrough(content, format),
+            "value" => render_value(content, scope, ctx),
+            // the indentation of snippet bodies matters
+            "snippet" => render_snippet(raw_content, scope, ctx),
+            // anything left couldn't be inherited, which javadoc renders as nothing as well
+            "inheritDoc" => String::new(),
+            _ => tag[..=end].to_owned(),
+        };
+        result.push_str(&converted);
+        rest = &tag[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Monospaced text, with all markup within it disabled
+pub(crate) fn monospace(text: &str, format: TextFormat) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    match format {
+        TextFormat::Asciidoc => format!("`{}`", passthrough(text, format)),
+        // markdown code spans take everything literally, but need a longer fence for backticks
+        TextFormat::Markdown if text.contains('`') => format!("`` {text} ``"),
+        TextFormat::Markdown => format!("`{text}`"),
+    }
+}
+
+/// Text as is, with all markup within it disabled
+fn passthrough(text: &str, format: TextFormat) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+    if format == TextFormat::Markdown {
+        return escape_markdown(text);
+    }
+    // a literal `+` can't be part of a constrained passthrough, so use the unconstrained form
+    if text.contains('+') {
+        format!("pass:[{}]", text.replace(']', "\\]"))
+    } else {
+        format!("+{text}+")
+    }
+}
+
+/// Escapes all characters with a meaning in markdown
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '\\' | '`' | '*' | '_' | '[' | ']' | '#' | '|' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Replaces `{@value}` with the initializer of the referenced constant, or of the documented
+/// field itself if there is no reference
+fn render_value(reference: &str, scope: Option<Node<'_>>, ctx: &FileContext) -> String {
+    let value = if reference.is_empty() {
+        scope.and_then(|scope| field_value(scope, ctx))
+    } else {
+        resolve_reference(reference, scope, ctx)
+            .and_then(|(_, member)| member)
+            .and_then(|member| member.value.clone())
+    };
+    match value {
+        Some(value) => monospace(&value, ctx.text_format()),
+        None => {
+            debug!("Could not resolve constant {reference:?}");
+            let reference = reference.strip_prefix('#').unwrap_or(reference);
+            monospace(&reference.replace('#', "."), ctx.text_format())
+        }
+    }
+}
+
+/// Byte index of the brace closing the inline tag at the start of `tag`, honoring nested braces
+pub(crate) fn closing_brace(tag: &str) -> Option<usize> {
+    let mut depth: usize = 0;
+    for (index, c) in tag.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Splits `Foo#bar(int, String) some label` into reference and label
+fn split_reference(content: &str) -> (&str, &str) {
+    let mut depth: usize = 0;
+    for (index, c) in content.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => {
+                return (&content[..index], content[index..].trim());
+            }
+            _ => (),
+        }
+    }
+    (content, "")
+}
+
+pub(super) fn render_link(
+    content: &str,
+    plain: bool,
+    scope: Option<Node<'_>>,
+    ctx: &FileContext,
+) -> String {
+    let (reference, label) = split_reference(content);
+    let label = if label.is_empty() {
+        let label = reference.strip_prefix('#').unwrap_or(reference);
+        label.replace('#', ".")
+    } else {
+        label.to_owned()
+    };
+    // like javadoc, {@link} uses code font while {@linkplain} doesn't
+    let label = if plain { label } else { format!("`{label}`") };
+
+    let Some((symbol, member)) = resolve_reference(reference, scope, ctx) else {
+        debug!("Could not resolve link target {reference:?}");
+        return label;
+    };
+    let page = &symbol.page;
+    let anchor = match member {
+        Some(member) => member.anchor.clone(),
+        None => type_anchor(&symbol.fqn),
+    };
+    let links = ctx.links().unwrap();
+    let same_page = *page == links.page;
+    let target = xref_target(&links.page, page, links.xref_root.as_deref());
+    let label = label.replace(']', "\\]");
+    match ctx.text_format() {
+        TextFormat::Asciidoc if same_page => format!("<<{anchor},{label}>>"),
+        TextFormat::Asciidoc => format!("xref:{target}#{anchor}[{label}]"),
+        TextFormat::Markdown if same_page => format!("[{label}](#{anchor})"),
+        TextFormat::Markdown => format!("[{label}]({target}#{anchor})"),
+    }
+}
+
+/// Resolves a reference like `Foo`, `pkg.Foo#bar` or `#bar(int)` to the documented type and, if
+/// the reference points to one, its member
+fn resolve_reference<'a>(
+    reference: &str,
+    scope: Option<Node<'_>>,
+    ctx: &'a FileContext,
+) -> Option<(&'a TypeSymbol, Option<&'a MemberSymbol>)> {
+    let enclosing = scope.and_then(|scope| local_type_name(scope, ctx));
+    let (typename, member) = match reference.split_once('#') {
+        Some((typename, member)) => (typename, Some(member)),
+        None => (reference, None),
+    };
+    let typename = if typename.is_empty() {
+        enclosing.clone()?
+    } else {
+        typename.to_owned()
+    };
+    let symbol = ctx.resolve_type(&typename, enclosing.as_deref())?;
+
+    let Some(member) = member else {
+        return Some((symbol, None));
+    };
+    let member = match member.split_once('(') {
+        Some((name, params)) => {
+            let params = params.trim_end_matches(')');
+            let paramtypes: Vec<String> = params
+                .split(',')
+                .map(|param| param.trim())
+                .filter(|param| !param.is_empty())
+                // parameters may be given with names, like in `#bar(int count)`
+                .map(|param| param.split_whitespace().next().unwrap_or(param))
+                .map(|param| erase_type(param, |t| ctx.qualify_type(t, enclosing.as_deref())))
+                .collect();
+            let anchor = method_anchor(&symbol.fqn, name, &paramtypes);
+            symbol.find_member(name, Some(&anchor))
+        }
+        None => {
+            let anchor = field_anchor(&symbol.fqn, member);
+            symbol.find_member(member, Some(&anchor))
+        }
+    };
+    Some((symbol, member))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr, sync::Arc};
+
+    use crate::{javadoc::symbols::SymbolTable, parser::parse_string};
+
+    use super::*;
+
+    const SOURCE: &str = "
+package com.acme;
+
+import com.other.Helper;
+
+/** docs */
+class Foo {
+  void bar(int count, String name) {}
+
+  /** the limit */
+  static final int LIMIT = 42;
+}";
+
+    fn symbols() -> Arc<SymbolTable> {
+        let mut symbols = SymbolTable::default();
+        symbols.insert(TypeSymbol {
+            fqn: "com.acme.Foo".to_owned(),
+            page: PathBuf::from("out/com/acme/Foo.adoc"),
+            members: vec![
+                MemberSymbol {
+                    name: "bar".to_owned(),
+                    anchor: "com.acme.Foo-bar-int-java.lang.String-".to_owned(),
+                    value: None,
+                },
+                MemberSymbol {
+                    name: "LIMIT".to_owned(),
+                    anchor: "com.acme.Foo-LIMIT".to_owned(),
+                    value: Some("42".to_owned()),
+                },
+            ],
+        });
+        symbols.insert(TypeSymbol {
+            fqn: "com.other.Helper".to_owned(),
+            page: PathBuf::from("out/com/other/Helper.adoc"),
+            members: vec![],
+        });
+        Arc::new(symbols)
+    }
+
+    fn convert(text: &str) -> String {
+        let tree = parse_string(SOURCE).unwrap();
+        let ctx = FileContext::from_str(SOURCE).unwrap().with_links(
+            symbols(),
+            PathBuf::from("out/com/acme/Foo.adoc"),
+            None,
+        );
+        let class = tree.root_node().named_child(3).unwrap();
+        convert_inline_tags(text, Some(class), &ctx)
+    }
+
+    #[test]
+    fn links_to_same_page() {
+        assert_eq!(
+            convert("see {@link #bar(int, String)} for details"),
+            "see <<com.acme.Foo-bar-int-java.lang.String-,`bar(int, String)`>> for details"
+        );
+        assert_eq!(
+            convert("{@link Foo#bar the bar}"),
+            "<<com.acme.Foo-bar-int-java.lang.String-,`the bar`>>"
+        );
+    }
+
+    #[test]
+    fn links_to_other_page() {
+        assert_eq!(
+            convert("{@linkplain Helper}"),
+            "xref:../other/Helper.adoc#com.other.Helper[Helper]"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_monospace() {
+        assert_eq!(convert("{@link java.util.List}"), "`java.util.List`");
+        assert_eq!(convert("{@link Missing a label}"), "`a label`");
+        assert_eq!(convert("{@linkplain Missing}"), "Missing");
+    }
+
+    #[test]
+    fn keeps_other_tags() {
+        assert_eq!(
+            convert("{@unknown {nested}} text"),
+            "{@unknown {nested}} text"
+        );
+    }
+
+    #[test]
+    fn converts_code() {
+        assert_eq!(
+            convert("use {@code Map<K, V> m = new HashMap<>() {{ put(a, b); }}} here"),
+            "use `+Map<K, V> m = new HashMap<>() {{ put(a, b); }}+` here"
+        );
+        assert_eq!(convert("{@code a + b}"), "`pass:[a + b]`");
+    }
+
+    #[test]
+    fn converts_literal() {
+        assert_eq!(convert("{@literal A<B>C *not bold*}"), "+A<B>C *not bold*+");
+    }
+
+    #[test]
+    fn converts_value() {
+        assert_eq!(convert("at most {@value #LIMIT}"), "at most `+42+`");
+        assert_eq!(convert("{@value Foo#LIMIT}"), "`+42+`");
+        assert_eq!(convert("{@value Missing#LIMIT}"), "`+Missing.LIMIT+`");
+    }
+
+    #[test]
+    fn converts_own_value() {
+        let tree = parse_string(SOURCE).unwrap();
+        let ctx = FileContext::from_str(SOURCE).unwrap();
+        let class = tree.root_node().named_child(3).unwrap();
+        let body = class.child_by_field_name("body").unwrap();
+        let field = body.named_child(2).unwrap();
+        assert_eq!(convert_inline_tags("{@value}", Some(field), &ctx), "`+42+`");
+    }
+}
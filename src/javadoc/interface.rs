@@ -2,10 +2,15 @@ use rust_i18n::t;
 use tracing::debug;
 use tree_sitter::Node;
 
-use crate::javadoc::{method::Method, prefix_hashes};
+use crate::javadoc::{
+    annotation_type::AnnotationType, class::Class, enumeration::Enum, field::Field, method::Method,
+    prefix_hashes, record::Record,
+};
 
 use super::{
-    comment::{find_block_comment, BlockComment},
+    anchor::{render_anchor, tagged_region},
+    comment::{find_documentation, BlockComment},
+    declaration::render_declaration,
     node_to_docable, FileContext, JavaDocable, JavaDocableElement,
 };
 
@@ -17,12 +22,18 @@ pub struct Interface<'a> {
     children: Vec<JavaDocableElement<'a>>,
 }
 
+impl<'a> Interface<'a> {
+    pub fn get_children(&self) -> &[JavaDocableElement<'a>] {
+        &self.children
+    }
+}
+
 impl<'a> JavaDocable<'a> for Interface<'a> {
     fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
     where
         Self: Sized,
     {
-        let comment = find_block_comment(node, ctx);
+        let comment = find_documentation(node, ctx);
         let mut cursor = node.walk();
         let body = node.child_by_field_name("body").unwrap();
 
@@ -54,7 +65,7 @@ impl<'a> JavaDocable<'a> for Interface<'a> {
         self.context
     }
 
-    fn get_comment(&self) -> &'a BlockComment {
+    fn get_comment(&self) -> &BlockComment<'a> {
         &self.comment
     }
 
@@ -69,28 +80,74 @@ impl<'a> JavaDocable<'a> for Interface<'a> {
     fn render(&'a self, level: u8) -> String {
         let prefix_hashes = prefix_hashes(level);
         let name = self.get_name();
-        let headline = format!("{prefix_hashes} {name}");
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes} {name}");
+        let declaration = render_declaration(self.get_node(), self.get_context());
         let content = self.get_comment();
         let content = format!("{content}");
 
+        let mut fields: Vec<&Field<'a>> = vec![];
         let mut methods: Vec<&Method<'a>> = vec![];
+        let mut classes: Vec<&Class<'a>> = vec![];
+        let mut interfaces: Vec<&Interface<'a>> = vec![];
+        let mut enums: Vec<&Enum<'a>> = vec![];
+        let mut records: Vec<&Record<'a>> = vec![];
+        let mut annotations: Vec<&AnnotationType<'a>> = vec![];
 
         // collect and group all children
         for child in &self.children {
-            if let JavaDocableElement::Method(method) = child {
-                methods.push(method);
+            match child {
+                JavaDocableElement::Field(field) => {
+                    fields.push(field);
+                }
+                JavaDocableElement::Method(method) => {
+                    methods.push(method);
+                }
+                JavaDocableElement::Class(class) => {
+                    classes.push(class);
+                }
+                JavaDocableElement::Interface(interface) => {
+                    interfaces.push(interface);
+                }
+                JavaDocableElement::Enum(en) => {
+                    enums.push(en);
+                }
+                JavaDocableElement::Record(record) => {
+                    records.push(record);
+                }
+                JavaDocableElement::AnnotationType(annotation) => {
+                    annotations.push(annotation);
+                }
+                _ => (),
             }
         }
+        let fields_headline = t!("field_headline", nesting = prefix_hashes);
         let methods_headline = t!("method_headline", nesting = prefix_hashes);
+        let classes_headline = t!("class_headline", nesting = prefix_hashes);
 
         //stringify all of the children with increased nesting levels
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        let fields = fields.join("\n");
         let methods: Vec<String> = methods
             .iter()
             .map(|&child| child.render(level + 1))
             .collect();
         let methods = methods.join("\n");
+        let mut classes: Vec<String> = classes
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        classes.extend(interfaces.iter().map(|&child| child.render(level + 1)));
+        classes.extend(enums.iter().map(|&child| child.render(level + 1)));
+        classes.extend(records.iter().map(|&child| child.render(level + 1)));
+        classes.extend(annotations.iter().map(|&child| child.render(level + 1)));
+        let classes = classes.join("\n");
 
-        format!("\n\n{headline}\n\n{content}{methods_headline}\n\n{methods}")
+        let rendered = format!("\n\n{headline}\n\n{declaration}{content}{fields_headline}\n\n{fields}{methods_headline}\n\n{methods}{classes_headline}\n\n{classes}");
+        tagged_region(self.get_node(), self.get_context(), rendered)
     }
 }
 
@@ -101,6 +158,7 @@ mod tests {
     use crate::parser::parse_string;
 
     use super::*;
+    use crate::javadoc::Selection;
 
     const NO_COMMENT: &str = "
 // interface
@@ -174,4 +232,67 @@ interface Animal {
             _ => panic!("Got something else than an interface????"),
         }
     }
+
+    #[test]
+    fn includes_undocumented() {
+        let sourcecode = NO_COMMENT;
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let filecontext = FileContext::from_str(sourcecode)
+            .unwrap()
+            .with_selection(Selection {
+                include_undocumented: true,
+                ..Default::default()
+            });
+
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &filecontext))
+            .collect();
+        assert_eq!(children.len(), 1);
+        let child = children.first().unwrap();
+        match child {
+            JavaDocableElement::Interface(child) => {
+                assert_eq!(child.get_name(), "Animal");
+                assert_eq!(child.children.len(), 2);
+                assert!(child.render(0).contains("No description."));
+            }
+            _ => panic!("Got something else than an interface????"),
+        }
+    }
+
+    #[test]
+    fn renders_nested_types() {
+        let sourcecode = "
+package com.acme;
+
+/** the api */
+interface Api {
+  /** the status */
+  enum Status { UP }
+}
+
+/** the client */
+class Client {
+  /** the listener */
+  interface Listener {}
+}";
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let filecontext = FileContext::from_str(sourcecode).unwrap();
+
+        let rendered: Vec<String> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &filecontext))
+            .map(|child| child.as_docable().render(0))
+            .collect();
+        assert_eq!(rendered.len(), 2);
+        assert!(rendered[0].contains("== Classes"));
+        assert!(rendered[0].contains("[[com.acme.Api.Status]]\n== Status"));
+        assert!(rendered[0].contains("the status"));
+        assert!(rendered[1].contains("[[com.acme.Client.Listener]]\n== Listener"));
+        assert!(rendered[1].contains("the listener"));
+    }
 }
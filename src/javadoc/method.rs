@@ -2,7 +2,15 @@ use tracing::debug;
 use tree_sitter::Node;
 
 use super::{
-    comment::{find_block_comment, BlockComment},
+    anchor::{render_anchor, tagged_region},
+    comment::{find_documentation, BlockComment},
+    declaration::{normalize_whitespace, render_declaration},
+    inheritance::inherits_javadoc,
+    prefix_hashes,
+    signature::{
+        parameters, render_parameters, render_returns, render_throws, render_type_parameters,
+        SIGNATURE_TAGS,
+    },
     FileContext, JavaDocable,
 };
 
@@ -18,13 +26,20 @@ impl<'a> JavaDocable<'a> for Method<'a> {
     where
         Self: Sized,
     {
-        let comment = find_block_comment(node, ctx);
+        let comment = find_documentation(node, ctx);
         if let Some(comment) = comment {
             Some(Self {
                 comment,
                 node,
                 context: ctx,
             })
+        } else if inherits_javadoc(node, ctx) {
+            debug!("Found a method without block comment, inheriting its documentation");
+            Some(Self {
+                comment: BlockComment::missing(node, ctx),
+                node,
+                context: ctx,
+            })
         } else {
             debug!("Found a field but no block comment, skipping");
             None
@@ -42,17 +57,37 @@ impl<'a> JavaDocable<'a> for Method<'a> {
     fn get_name(&self) -> String {
         let node = self.get_node();
         let ctx = self.get_context();
-        let nodetype = node.child_by_field_name("type").unwrap();
         let name = node.child_by_field_name("name").unwrap();
-        let params = node.child_by_field_name("parameters").unwrap();
-        let nodetype = ctx.source_for_range(&nodetype.range());
         let name = ctx.source_for_range(&name.range());
-        let params = ctx.source_for_range(&params.range());
-
-        format!("{nodetype} {name} {params}")
+        let paramtypes: Vec<String> = parameters(node, ctx)
+            .iter()
+            .map(|param| normalize_whitespace(&param.paramtype))
+            .collect();
+        let paramtypes = paramtypes.join(", ");
+        format!("{name}({paramtypes})")
     }
 
-    fn get_comment(&self) -> &'a BlockComment {
+    fn get_comment(&self) -> &BlockComment<'a> {
         &self.comment
     }
+
+    fn render(&'a self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let name = self.get_name();
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes}= {name}");
+        let node = self.get_node();
+        let ctx = self.get_context();
+        let comment = self.get_comment();
+        let javadoc = comment.rendered_javadoc();
+        let declaration = render_declaration(node, ctx);
+        let content = comment.render_without(&SIGNATURE_TAGS);
+        let type_parameters = render_type_parameters(node, ctx, &javadoc);
+        let parameters = render_parameters(node, ctx, &javadoc);
+        let returns = render_returns(node, ctx, &javadoc);
+        let throws = render_throws(node, ctx, &javadoc);
+        let tables = format!("{type_parameters}{parameters}{returns}{throws}");
+        let rendered = format!("\n\n{headline}\n\n{declaration}{content}{tables}");
+        tagged_region(self.get_node(), self.get_context(), rendered)
+    }
 }
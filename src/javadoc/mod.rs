@@ -1,54 +1,457 @@
+pub mod anchor;
+pub mod annotation_element;
+pub mod annotation_type;
 pub mod class;
 pub mod comment;
 pub mod constructor;
+pub mod declaration;
+pub mod enum_constant;
+pub mod enumeration;
 pub mod field;
+pub mod inheritance;
+pub mod inline;
 pub mod interface;
 pub mod method;
+pub mod model;
+pub mod module;
+pub mod package;
+pub mod record;
+pub mod signature;
+pub mod snippet;
+pub mod symbols;
+pub mod tags;
+pub mod visibility;
 
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr, sync::Arc};
 
+use anchor::{render_anchor, tagged_region};
+use annotation_element::AnnotationElement;
+use annotation_type::AnnotationType;
 use class::Class;
 use comment::BlockComment;
 use constructor::Constructor;
+use declaration::render_declaration;
+use enum_constant::EnumConstant;
+use enumeration::Enum;
 use field::Field;
 use interface::Interface;
 use method::Method;
+use module::Module;
+use package::Package;
+use record::Record;
+use symbols::{SymbolTable, TypeSymbol};
 use tracing::{debug, trace};
 use tree_sitter::{Node, Range};
+use visibility::{is_visible, Visibility};
 
+use crate::parser::parse_string;
+
+/// Public top level types of `java.lang`, whose simple names are usable without an import
+const JAVA_LANG_TYPES: &[&str] = &[
+    "AbstractMethodError",
+    "Appendable",
+    "ArithmeticException",
+    "ArrayIndexOutOfBoundsException",
+    "ArrayStoreException",
+    "AssertionError",
+    "AutoCloseable",
+    "Boolean",
+    "BootstrapMethodError",
+    "Byte",
+    "CharSequence",
+    "Character",
+    "Class",
+    "ClassCastException",
+    "ClassCircularityError",
+    "ClassFormatError",
+    "ClassLoader",
+    "ClassNotFoundException",
+    "ClassValue",
+    "CloneNotSupportedException",
+    "Cloneable",
+    "Comparable",
+    "Deprecated",
+    "Double",
+    "Enum",
+    "EnumConstantNotPresentException",
+    "Error",
+    "Exception",
+    "ExceptionInInitializerError",
+    "Float",
+    "FunctionalInterface",
+    "IllegalAccessError",
+    "IllegalAccessException",
+    "IllegalArgumentException",
+    "IllegalCallerException",
+    "IllegalMonitorStateException",
+    "IllegalStateException",
+    "IllegalThreadStateException",
+    "IncompatibleClassChangeError",
+    "IndexOutOfBoundsException",
+    "InheritableThreadLocal",
+    "InstantiationError",
+    "InstantiationException",
+    "Integer",
+    "InternalError",
+    "InterruptedException",
+    "Iterable",
+    "LayerInstantiationException",
+    "LinkageError",
+    "Long",
+    "MatchException",
+    "Math",
+    "Module",
+    "ModuleLayer",
+    "NegativeArraySizeException",
+    "NoClassDefFoundError",
+    "NoSuchFieldError",
+    "NoSuchFieldException",
+    "NoSuchMethodError",
+    "NoSuchMethodException",
+    "NullPointerException",
+    "Number",
+    "NumberFormatException",
+    "Object",
+    "OutOfMemoryError",
+    "Override",
+    "Package",
+    "Process",
+    "ProcessBuilder",
+    "ProcessHandle",
+    "Readable",
+    "Record",
+    "ReflectiveOperationException",
+    "Runnable",
+    "Runtime",
+    "RuntimeException",
+    "RuntimePermission",
+    "SafeVarargs",
+    "SecurityException",
+    "SecurityManager",
+    "Short",
+    "StackOverflowError",
+    "StackTraceElement",
+    "StackWalker",
+    "StrictMath",
+    "String",
+    "StringBuffer",
+    "StringBuilder",
+    "StringIndexOutOfBoundsException",
+    "SuppressWarnings",
+    "System",
+    "Thread",
+    "ThreadDeath",
+    "ThreadGroup",
+    "ThreadLocal",
+    "Throwable",
+    "TypeNotPresentException",
+    "UnknownError",
+    "UnsatisfiedLinkError",
+    "UnsupportedClassVersionError",
+    "UnsupportedOperationException",
+    "VerifyError",
+    "VirtualMachineError",
+    "Void",
+    "WrongThreadException",
+];
+
+pub const TYPE_DECLARATIONS: &[&str] = &[
+    "class_declaration",
+    "interface_declaration",
+    "enum_declaration",
+    "record_declaration",
+    "annotation_type_declaration",
+];
+
+#[derive(Debug, Default)]
+pub struct FileContext {
+    source: String,
+    package: Option<String>,
+    imports: Vec<String>,
+    /// All types declared in this file, named relative to the package like `Outer.Inner`
+    local_types: Vec<String>,
+    links: Option<Links>,
+    /// Directories external `{@snippet}` files are looked up in, in order
+    snippet_dirs: Vec<PathBuf>,
+    selection: Selection,
+    /// Wrap every type and member into a tagged region, for includes
+    tagged_regions: bool,
+    /// Markup the documentation text is converted to
+    text_format: TextFormat,
+}
+
+/// Markup language the text of documentation comments is converted to
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TextFormat {
+    #[default]
+    Asciidoc,
+    /// CommonMark with the GitHub flavored tables and strikethrough
+    Markdown,
+}
+
+/// Which declarations end up in the generated documentation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Selection {
+    /// Also render declarations without documentation comment, with a placeholder description
+    pub include_undocumented: bool,
+    /// Minimum access level of documented types and members
+    pub visibility: Visibility,
+}
+
+/// Everything needed to link to other documented elements from within a generated page
 #[derive(Debug)]
-pub struct FileContext(String);
+pub struct Links {
+    pub symbols: Arc<SymbolTable>,
+    /// Path of the page generated for the file
+    pub page: PathBuf,
+    /// Directory `xref:` targets are relative to, instead of the generated page
+    pub xref_root: Option<PathBuf>,
+}
 
 impl FileContext {
     pub fn source_for_range(&self, range: &Range) -> &str {
-        let sourcecode = &self.0;
+        let sourcecode = &self.source;
         (&sourcecode[range.start_byte..range.end_byte]) as _
     }
+
+    /// Enables links to the elements of `symbols`, from the page generated at `page`, with link
+    /// targets relative to `xref_root` if given
+    pub fn with_links(
+        self,
+        symbols: Arc<SymbolTable>,
+        page: PathBuf,
+        xref_root: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            links: Some(Links {
+                symbols,
+                page,
+                xref_root,
+            }),
+            ..self
+        }
+    }
+
+    pub fn links(&self) -> Option<&Links> {
+        self.links.as_ref()
+    }
+
+    /// Sets the directories to look up external `{@snippet}` files in
+    pub fn with_snippet_dirs(self, snippet_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            snippet_dirs,
+            ..self
+        }
+    }
+
+    pub fn snippet_dirs(&self) -> &[PathBuf] {
+        &self.snippet_dirs
+    }
+
+    /// Sets which declarations are documented
+    pub fn with_selection(self, selection: Selection) -> Self {
+        Self { selection, ..self }
+    }
+
+    pub fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    /// Enables tagged regions around all types and members
+    pub fn with_tagged_regions(self, tagged_regions: bool) -> Self {
+        Self {
+            tagged_regions,
+            ..self
+        }
+    }
+
+    pub fn tagged_regions(&self) -> bool {
+        self.tagged_regions
+    }
+
+    /// Sets the markup the documentation text is converted to
+    pub fn with_text_format(self, text_format: TextFormat) -> Self {
+        Self {
+            text_format,
+            ..self
+        }
+    }
+
+    pub fn text_format(&self) -> TextFormat {
+        self.text_format
+    }
+
+    pub fn package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
+
+    /// Whether any types are declared in this file, which `package-info.java` doesn't
+    pub fn declares_types(&self) -> bool {
+        !self.local_types.is_empty()
+    }
+
+    /// Fully qualified name of a type declared in this file
+    pub fn fqn(&self, local_type: &str) -> String {
+        match &self.package {
+            Some(package) => format!("{package}.{local_type}"),
+            None => local_type.to_owned(),
+        }
+    }
+
+    /// Qualifies a type name as used from within `enclosing` (relative to the package), based
+    /// on the declarations and imports of this file only. Types that can't be found are assumed to
+    /// live in the same package.
+    pub fn qualify_type(&self, name: &str, enclosing: Option<&str>) -> String {
+        const PRIMITIVES: &[&str] = &[
+            "boolean", "byte", "char", "short", "int", "long", "float", "double", "void",
+        ];
+        if PRIMITIVES.contains(&name) {
+            return name.to_owned();
+        }
+        let (first, rest) = match name.split_once('.') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+        // anything starting lowercase is most likely already a package
+        if first.starts_with(|c: char| c.is_lowercase()) {
+            return name.to_owned();
+        }
+        let with_rest = |qualified: String| match rest {
+            Some(rest) => format!("{qualified}.{rest}"),
+            None => qualified,
+        };
+
+        // nested types shadow everything else
+        let mut scope = enclosing;
+        while let Some(current) = scope {
+            let candidate = format!("{current}.{first}");
+            if self.local_types.contains(&candidate) {
+                return with_rest(self.fqn(&candidate));
+            }
+            scope = current.rsplit_once('.').map(|(outer, _)| outer);
+        }
+        if self.local_types.iter().any(|local| local == first) {
+            return with_rest(self.fqn(first));
+        }
+        let imported = self
+            .imports
+            .iter()
+            .find(|import| import.rsplit('.').next() == Some(first));
+        if let Some(imported) = imported {
+            return with_rest(imported.clone());
+        }
+        if JAVA_LANG_TYPES.contains(&first) {
+            return with_rest(format!("java.lang.{first}"));
+        }
+        with_rest(self.fqn(first))
+    }
+
+    /// Looks up a referenced type in the symbol table, also trying wildcard imports
+    pub fn resolve_type(&self, name: &str, enclosing: Option<&str>) -> Option<&TypeSymbol> {
+        let symbols = &self.links.as_ref()?.symbols;
+        let qualified = self.qualify_type(name, enclosing);
+        if let Some(symbol) = symbols.get(&qualified) {
+            return Some(symbol);
+        }
+        if let Some(symbol) = symbols.get(name) {
+            return Some(symbol);
+        }
+        self.imports
+            .iter()
+            .filter_map(|import| import.strip_suffix(".*"))
+            .find_map(|package| symbols.get(&format!("{package}.{name}")))
+    }
+
+    fn read_declarations(&mut self, node: Node<'_>, enclosing: Option<&str>) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.grammar_name() {
+                "package_declaration" => {
+                    self.package = Some(package::package_name(child, self));
+                }
+                "import_declaration" => {
+                    let import = self.source_for_range(&child.range());
+                    let import = import
+                        .trim_start_matches("import")
+                        .trim()
+                        .trim_start_matches("static ")
+                        .trim_end_matches(';');
+                    let import: String = import.chars().filter(|c| !c.is_whitespace()).collect();
+                    self.imports.push(import);
+                }
+                name if TYPE_DECLARATIONS.contains(&name) => {
+                    let Some(type_name) = child.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let type_name = self.source_for_range(&type_name.range());
+                    let type_name = match enclosing {
+                        Some(enclosing) => format!("{enclosing}.{type_name}"),
+                        None => type_name.to_owned(),
+                    };
+                    if let Some(body) = child.child_by_field_name("body") {
+                        self.read_declarations(body, Some(&type_name));
+                    }
+                    self.local_types.push(type_name);
+                }
+                "enum_body_declarations" => self.read_declarations(child, enclosing),
+                _ => (),
+            }
+        }
+    }
 }
 
 impl FromStr for FileContext {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+        let mut ctx = Self {
+            source: s.to_string(),
+            ..Default::default()
+        };
+        let tree = parse_string(s)?;
+        ctx.read_declarations(tree.root_node(), None);
+        Ok(ctx)
     }
 }
 
+/// Name of the innermost type declaration `node` belongs to, relative to the package like
+/// `Outer.Inner`. For type declarations this is their own name.
+pub fn local_type_name(node: Node<'_>, ctx: &FileContext) -> Option<String> {
+    let mut names: Vec<&str> = vec![];
+    let mut current = Some(node);
+    while let Some(node) = current {
+        if TYPE_DECLARATIONS.contains(&node.grammar_name()) {
+            if let Some(name) = node.child_by_field_name("name") {
+                names.push(ctx.source_for_range(&name.range()));
+            }
+        }
+        current = node.parent();
+    }
+    if names.is_empty() {
+        return None;
+    }
+    names.reverse();
+    Some(names.join("."))
+}
+
 pub trait JavaDocable<'a> {
     fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
     where
         Self: Sized;
     fn get_node(&self) -> Node<'_>;
     fn get_context(&self) -> &'a FileContext;
-    fn get_comment(&self) -> &'a BlockComment;
+    fn get_comment(&self) -> &BlockComment<'a>;
     fn get_name(&self) -> String;
     fn render(&'a self, level: u8) -> String {
         let prefix_hashes = prefix_hashes(level);
         let name = self.get_name();
-        let headline = format!("{prefix_hashes}= {name}");
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes}= {name}");
+        let declaration = render_declaration(self.get_node(), self.get_context());
         let content = self.get_comment();
         let content = format!("{content}");
-        format!("\n\n{headline}\n\n{content}")
+        let rendered = format!("\n\n{headline}\n\n{declaration}{content}");
+        tagged_region(self.get_node(), self.get_context(), rendered)
     }
 }
 
@@ -59,11 +462,71 @@ pub enum JavaDocableElement<'a> {
     Method(Method<'a>),
     Constructor(Constructor<'a>),
     Interface(Interface<'a>),
+    Enum(Enum<'a>),
+    EnumConstant(EnumConstant<'a>),
+    Record(Record<'a>),
+    AnnotationType(AnnotationType<'a>),
+    AnnotationElement(AnnotationElement<'a>),
+    Package(Package<'a>),
+    Module(Module<'a>),
+}
+
+impl<'a> JavaDocableElement<'a> {
+    pub fn get_node(&self) -> Node<'_> {
+        match self {
+            Self::Class(element) => element.get_node(),
+            Self::Field(element) => element.get_node(),
+            Self::Method(element) => element.get_node(),
+            Self::Constructor(element) => element.get_node(),
+            Self::Interface(element) => element.get_node(),
+            Self::Enum(element) => element.get_node(),
+            Self::EnumConstant(element) => element.get_node(),
+            Self::Record(element) => element.get_node(),
+            Self::AnnotationType(element) => element.get_node(),
+            Self::AnnotationElement(element) => element.get_node(),
+            Self::Package(element) => element.get_node(),
+            Self::Module(element) => element.get_node(),
+        }
+    }
+
+    /// The documented element, for everything all elements have in common
+    pub fn as_docable(&self) -> &dyn JavaDocable<'a> {
+        match self {
+            Self::Class(element) => element,
+            Self::Field(element) => element,
+            Self::Method(element) => element,
+            Self::Constructor(element) => element,
+            Self::Interface(element) => element,
+            Self::Enum(element) => element,
+            Self::EnumConstant(element) => element,
+            Self::Record(element) => element,
+            Self::AnnotationType(element) => element,
+            Self::AnnotationElement(element) => element,
+            Self::Package(element) => element,
+            Self::Module(element) => element,
+        }
+    }
+
+    /// Documented members and nested types, empty for anything but types
+    pub fn get_children(&self) -> &[JavaDocableElement<'a>] {
+        match self {
+            Self::Class(element) => element.get_children(),
+            Self::Interface(element) => element.get_children(),
+            Self::Enum(element) => element.get_children(),
+            Self::Record(element) => element.get_children(),
+            Self::AnnotationType(element) => element.get_children(),
+            _ => &[],
+        }
+    }
 }
 
 pub fn node_to_docable<'a>(node: Node<'a>, ctx: &'a FileContext) -> Option<JavaDocableElement<'a>> {
     let name = node.grammar_name();
     trace!("Handling a {name} node");
+    if !is_visible(node, ctx) {
+        debug!("Skipping {name} below the documented visibility");
+        return None;
+    }
     match name {
         "class_declaration" => {
             debug!("Found a class declaration");
@@ -75,12 +538,12 @@ pub fn node_to_docable<'a>(node: Node<'a>, ctx: &'a FileContext) -> Option<JavaD
             let method = Method::new(ctx, node);
             method.map(JavaDocableElement::Method)
         }
-        "field_declaration" => {
+        "field_declaration" | "constant_declaration" => {
             debug!("Found a field declaration");
             let field = Field::new(ctx, node);
             field.map(JavaDocableElement::Field)
         }
-        "constructor_declaration" => {
+        "constructor_declaration" | "compact_constructor_declaration" => {
             debug!("Found a constructor declaration");
             let constructor = Constructor::new(ctx, node);
             constructor.map(JavaDocableElement::Constructor)
@@ -90,12 +553,49 @@ pub fn node_to_docable<'a>(node: Node<'a>, ctx: &'a FileContext) -> Option<JavaD
             let interface = Interface::new(ctx, node);
             interface.map(JavaDocableElement::Interface)
         }
+        "enum_declaration" => {
+            debug!("Found an enum declaration");
+            let en = Enum::new(ctx, node);
+            en.map(JavaDocableElement::Enum)
+        }
+        "enum_constant" => {
+            debug!("Found an enum constant");
+            let constant = EnumConstant::new(ctx, node);
+            constant.map(JavaDocableElement::EnumConstant)
+        }
+        "record_declaration" => {
+            debug!("Found a record declaration");
+            let record = Record::new(ctx, node);
+            record.map(JavaDocableElement::Record)
+        }
+        "annotation_type_declaration" => {
+            debug!("Found an annotation type declaration");
+            let annotation = AnnotationType::new(ctx, node);
+            annotation.map(JavaDocableElement::AnnotationType)
+        }
+        "annotation_type_element_declaration" => {
+            debug!("Found an annotation type element declaration");
+            let element = AnnotationElement::new(ctx, node);
+            element.map(JavaDocableElement::AnnotationElement)
+        }
+        "package_declaration" => {
+            debug!("Found a package declaration");
+            let package = Package::new(ctx, node);
+            package.map(JavaDocableElement::Package)
+        }
+        "module_declaration" => {
+            debug!("Found a module declaration");
+            let module = Module::new(ctx, node);
+            module.map(JavaDocableElement::Module)
+        }
         _ => None,
     }
 }
 
+/// Section markers of a headline on `level`. Asciidoctor knows no sections below level 5, so
+/// deeper types stay on level 4 where their members and section headlines are one level deeper.
 pub fn prefix_hashes(level: u8) -> String {
-    let level: usize = level.into();
+    let level: usize = level.min(4).into();
     let prefix_hashes = vec!["="; level].join("");
     format!("={}", prefix_hashes)
 }
@@ -0,0 +1,365 @@
+//! A serializable model of the documented elements of a file, independent of how it is laid out.
+//! User supplied templates are rendered from it.
+
+use serde::Serialize;
+use tree_sitter::Node;
+
+use super::{
+    anchor::node_anchor,
+    declaration::{annotations, declaration, modifiers},
+    local_type_name,
+    module::ModuleDirective,
+    signature::{documented_returns, documented_throws, parameters, supertypes, type_parameters},
+    symbols::field_value,
+    tags::BlockTag,
+    visibility::{access, Visibility},
+    FileContext, JavaDocableElement,
+};
+
+/// All documented elements of a single source file
+#[derive(Debug, Serialize)]
+pub struct Page {
+    /// Declared package of the file, if any
+    pub package: Option<String>,
+    pub elements: Vec<Element>,
+}
+
+/// What kind of declaration an element documents
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElementKind {
+    Class,
+    Interface,
+    Enum,
+    Record,
+    AnnotationType,
+    Field,
+    Method,
+    Constructor,
+    EnumConstant,
+    AnnotationElement,
+    Package,
+    Module,
+}
+
+/// A documented type, member, package or module
+#[derive(Debug, Serialize)]
+pub struct Element {
+    pub kind: ElementKind,
+    /// Name as used in headlines, like `map(Function<T, R>)` for methods
+    pub name: String,
+    /// Fully qualified name, members are appended to their type like `com.acme.Foo#bar(int)`
+    pub qualified_name: String,
+    /// Anchor of the element on its page, for types and members
+    pub anchor: Option<String>,
+    /// Declaration as written in the source, without body and comments
+    pub declaration: String,
+    pub annotations: Vec<String>,
+    pub modifiers: Vec<String>,
+    pub visibility: Visibility,
+    /// Type parameters of generic types, methods and constructors, like `T extends Number`
+    pub type_parameters: Vec<String>,
+    /// Superclass of classes, or extended interfaces of interfaces
+    pub extends: Vec<String>,
+    /// Implemented interfaces of classes, enums and records
+    pub implements: Vec<String>,
+    /// Permitted subtypes of sealed types
+    pub permits: Vec<String>,
+    /// Description of the documentation comment, already converted to asciidoc or markdown
+    pub description: String,
+    /// All block tags of the documentation comment, in order
+    pub tags: Vec<Tag>,
+    /// Parameters of methods and constructors, components of records
+    pub parameters: Vec<Parameter>,
+    /// Return value of methods, unless `void` and undocumented
+    pub returns: Option<Returns>,
+    /// Declared and documented exceptions of methods and constructors
+    pub throws: Vec<Thrown>,
+    /// Initializer of fields
+    pub value: Option<String>,
+    /// Default value of annotation elements
+    pub default_value: Option<String>,
+    /// Values of the `@Retention` meta-annotation of annotation types
+    pub retention: Option<Vec<String>>,
+    /// Values of the `@Target` meta-annotation of annotation types
+    pub target: Option<Vec<String>>,
+    /// Directives of modules
+    pub directives: Vec<ModuleDirective>,
+    pub location: Location,
+    /// Documented members and nested types
+    pub children: Vec<Element>,
+}
+
+/// A block tag like `@since 1.2`
+#[derive(Debug, Serialize)]
+pub struct Tag {
+    pub name: String,
+    /// Parameter name of `@param` and `@serialField`, exception of `@throws`
+    pub argument: Option<String>,
+    /// Whole content of the tag, including the argument
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub paramtype: String,
+    /// Description of the matching `@param` tag, empty if undocumented
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Returns {
+    #[serde(rename = "type")]
+    pub returntype: String,
+    /// Description of the `@return` tag, empty if undocumented
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Thrown {
+    #[serde(rename = "type")]
+    pub exception: String,
+    /// Description of the matching `@throws` tag, empty if undocumented
+    pub description: String,
+}
+
+/// Position of the declaration in the source file, with lines and columns starting at 1
+#[derive(Debug, Serialize)]
+pub struct Location {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl Location {
+    fn of(node: Node<'_>) -> Self {
+        let range = node.range();
+        Self {
+            start_line: range.start_point.row + 1,
+            start_column: range.start_point.column + 1,
+            end_line: range.end_point.row + 1,
+            end_column: range.end_point.column + 1,
+            start_byte: range.start_byte,
+            end_byte: range.end_byte,
+        }
+    }
+}
+
+/// Builds the model of all documented elements of a file
+pub fn page(children: &[JavaDocableElement<'_>], ctx: &FileContext) -> Page {
+    Page {
+        package: ctx.package().map(|package| package.to_owned()),
+        elements: children.iter().map(element).collect(),
+    }
+}
+
+/// Builds the model of a single documented element and its children
+pub fn element(docable: &JavaDocableElement<'_>) -> Element {
+    let kind = match docable {
+        JavaDocableElement::Class(_) => ElementKind::Class,
+        JavaDocableElement::Interface(_) => ElementKind::Interface,
+        JavaDocableElement::Enum(_) => ElementKind::Enum,
+        JavaDocableElement::Record(_) => ElementKind::Record,
+        JavaDocableElement::AnnotationType(_) => ElementKind::AnnotationType,
+        JavaDocableElement::Field(_) => ElementKind::Field,
+        JavaDocableElement::Method(_) => ElementKind::Method,
+        JavaDocableElement::Constructor(_) => ElementKind::Constructor,
+        JavaDocableElement::EnumConstant(_) => ElementKind::EnumConstant,
+        JavaDocableElement::AnnotationElement(_) => ElementKind::AnnotationElement,
+        JavaDocableElement::Package(_) => ElementKind::Package,
+        JavaDocableElement::Module(_) => ElementKind::Module,
+    };
+    let documented = docable.as_docable();
+    let node = documented.get_node();
+    let ctx = documented.get_context();
+    let name = documented.get_name();
+    let javadoc = documented.get_comment().rendered_javadoc();
+
+    let qualified_name = match (kind, local_type_name(node, ctx)) {
+        (ElementKind::Package | ElementKind::Module, _) | (_, None) => name.clone(),
+        (
+            ElementKind::Class
+            | ElementKind::Interface
+            | ElementKind::Enum
+            | ElementKind::Record
+            | ElementKind::AnnotationType,
+            Some(local_type),
+        ) => ctx.fqn(&local_type),
+        (_, Some(local_type)) => format!("{}#{name}", ctx.fqn(&local_type)),
+    };
+
+    let parameters = match kind {
+        ElementKind::Method | ElementKind::Constructor | ElementKind::Record => {
+            parameters(node, ctx)
+                .into_iter()
+                .map(|param| Parameter {
+                    description: javadoc.param(&param.name).unwrap_or_default().to_owned(),
+                    name: param.name,
+                    paramtype: param.paramtype,
+                })
+                .collect()
+        }
+        _ => vec![],
+    };
+    let returns = match kind {
+        ElementKind::Method => {
+            documented_returns(node, ctx, &javadoc).map(|(returntype, description)| Returns {
+                returntype,
+                description: description.unwrap_or_default().to_owned(),
+            })
+        }
+        _ => None,
+    };
+    let throws = match kind {
+        ElementKind::Method | ElementKind::Constructor => documented_throws(node, ctx, &javadoc)
+            .into_iter()
+            .map(|(exception, description)| Thrown {
+                exception,
+                description: description.to_owned(),
+            })
+            .collect(),
+        _ => vec![],
+    };
+    let (retention, target) = match docable {
+        JavaDocableElement::AnnotationType(annotation) => (
+            annotation.meta_annotation_values("Retention"),
+            annotation.meta_annotation_values("Target"),
+        ),
+        _ => (None, None),
+    };
+    let default_value = match docable {
+        JavaDocableElement::AnnotationElement(element) => {
+            element.get_default().map(|value| value.to_owned())
+        }
+        _ => None,
+    };
+    let supertypes = supertypes(node, ctx);
+    let (modifiers, directives) = match docable {
+        JavaDocableElement::Module(module) => {
+            let modifiers = match module.is_open() {
+                true => vec!["open".to_owned()],
+                false => vec![],
+            };
+            (modifiers, module.directives())
+        }
+        _ => (modifiers(node, ctx), vec![]),
+    };
+
+    Element {
+        kind,
+        qualified_name,
+        anchor: node_anchor(node, ctx),
+        declaration: declaration(node, ctx),
+        annotations: annotations(node, ctx),
+        modifiers,
+        visibility: access(node, ctx),
+        type_parameters: type_parameters(node, ctx),
+        extends: supertypes.extends,
+        implements: supertypes.implements,
+        permits: supertypes.permits,
+        description: javadoc.description.clone(),
+        tags: javadoc
+            .tags
+            .iter()
+            .map(|tag| Tag {
+                name: tag.name().to_owned(),
+                argument: match tag {
+                    BlockTag::Param { name, .. } | BlockTag::SerialField { name, .. } => {
+                        Some(name.clone())
+                    }
+                    BlockTag::Throws { exception, .. } => Some(exception.clone()),
+                    _ => None,
+                },
+                content: tag.content(),
+            })
+            .collect(),
+        parameters,
+        returns,
+        throws,
+        value: field_value(node, ctx),
+        default_value,
+        retention,
+        target,
+        directives,
+        location: Location::of(node),
+        children: docable.get_children().iter().map(element).collect(),
+        name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{javadoc::node_to_docable, parser::parse_string};
+
+    use super::*;
+
+    const INPUT: &str = "
+package com.acme;
+
+/** A box. */
+public final class Box {
+    /**
+     * Maps the content.
+     * @param limit at most this many
+     * @return the mapped content
+     * @throws IllegalStateException when empty
+     * @since 1.2
+     */
+    public List<String> map(int limit) {
+        return null;
+    }
+
+    /** The name. */
+    static final String NAME = \"box\";
+}";
+
+    #[test]
+    fn builds_model() {
+        let tree = parse_string(INPUT).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let ctx = FileContext::from_str(INPUT).unwrap();
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &ctx))
+            .collect();
+        let page = page(&children, &ctx);
+        assert_eq!(page.package.as_deref(), Some("com.acme"));
+        assert_eq!(page.elements.len(), 1);
+
+        let class = &page.elements[0];
+        assert_eq!(class.kind, ElementKind::Class);
+        assert_eq!(class.qualified_name, "com.acme.Box");
+        assert_eq!(class.modifiers, vec!["public", "final"]);
+        assert_eq!(class.description, "A box.");
+        assert_eq!(class.location.start_line, 5);
+        assert_eq!(class.children.len(), 2);
+
+        let method = &class.children[0];
+        assert_eq!(method.kind, ElementKind::Method);
+        assert_eq!(method.name, "map(int)");
+        assert_eq!(method.qualified_name, "com.acme.Box#map(int)");
+        assert_eq!(method.anchor.as_deref(), Some("com.acme.Box-map-int-"));
+        assert_eq!(method.parameters[0].paramtype, "int");
+        assert_eq!(method.parameters[0].description, "at most this many");
+        let returns = method.returns.as_ref().unwrap();
+        assert_eq!(returns.returntype, "List<String>");
+        assert_eq!(returns.description, "the mapped content");
+        assert_eq!(method.throws[0].exception, "IllegalStateException");
+        assert_eq!(method.tags.len(), 4);
+        assert_eq!(method.tags[0].argument.as_deref(), Some("limit"));
+        assert_eq!(method.tags[3].name, "since");
+
+        let field = &class.children[1];
+        assert_eq!(field.kind, ElementKind::Field);
+        assert_eq!(field.visibility, Visibility::Package);
+        assert_eq!(field.value.as_deref(), Some("\"box\""));
+    }
+}
@@ -0,0 +1,350 @@
+use rust_i18n::t;
+use serde::Serialize;
+use tracing::debug;
+use tree_sitter::Node;
+
+use super::{
+    comment::{find_documentation, BlockComment},
+    prefix_hashes, FileContext, JavaDocable,
+};
+
+#[derive(Debug)]
+pub struct Module<'a> {
+    comment: BlockComment<'a>,
+    node: Node<'a>,
+    context: &'a FileContext,
+}
+
+/// A single `requires`, `exports`, `opens`, `uses` or `provides` directive of a module
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "directive", rename_all = "snake_case")]
+pub enum ModuleDirective {
+    Requires {
+        module: String,
+        transitive: bool,
+        #[serde(rename = "static")]
+        is_static: bool,
+    },
+    Exports {
+        package: String,
+        to: Vec<String>,
+    },
+    Opens {
+        package: String,
+        to: Vec<String>,
+    },
+    Uses {
+        service: String,
+    },
+    Provides {
+        service: String,
+        with: Vec<String>,
+    },
+}
+
+impl Module<'_> {
+    pub fn is_open(&self) -> bool {
+        let mut cursor = self.node.walk();
+        let is_open = self
+            .node
+            .children(&mut cursor)
+            .any(|child| child.grammar_name() == "open");
+        is_open
+    }
+
+    pub fn directives(&self) -> Vec<ModuleDirective> {
+        let ctx = self.get_context();
+        let source = |node: Node<'_>| ctx.source_for_range(&node.range()).to_owned();
+        let body = self.node.child_by_field_name("body").unwrap();
+        let mut cursor = body.walk();
+
+        body.named_children(&mut cursor)
+            .filter_map(|directive| {
+                let mut cursor = directive.walk();
+                match directive.grammar_name() {
+                    "requires_module_directive" => {
+                        let module = directive.child_by_field_name("module").unwrap();
+                        let modifiers: Vec<String> = directive
+                            .children_by_field_name("modifiers", &mut cursor)
+                            .map(source)
+                            .collect();
+                        Some(ModuleDirective::Requires {
+                            module: source(module),
+                            transitive: modifiers.iter().any(|m| m == "transitive"),
+                            is_static: modifiers.iter().any(|m| m == "static"),
+                        })
+                    }
+                    "exports_module_directive" => {
+                        let package = directive.child_by_field_name("package").unwrap();
+                        let to = directive
+                            .children_by_field_name("modules", &mut cursor)
+                            .map(source)
+                            .collect();
+                        Some(ModuleDirective::Exports {
+                            package: source(package),
+                            to,
+                        })
+                    }
+                    "opens_module_directive" => {
+                        let package = directive.child_by_field_name("package").unwrap();
+                        let to = directive
+                            .children_by_field_name("modules", &mut cursor)
+                            .map(source)
+                            .collect();
+                        Some(ModuleDirective::Opens {
+                            package: source(package),
+                            to,
+                        })
+                    }
+                    "uses_module_directive" => {
+                        let service = directive.child_by_field_name("type").unwrap();
+                        Some(ModuleDirective::Uses {
+                            service: source(service),
+                        })
+                    }
+                    "provides_module_directive" => {
+                        let service = directive.child_by_field_name("provided").unwrap();
+                        // the grammar only attaches the field name to the later providers,
+                        // so take every named child after the provided service instead
+                        let with = directive
+                            .named_children(&mut cursor)
+                            .filter(|child| child.id() != service.id())
+                            .map(source)
+                            .collect();
+                        Some(ModuleDirective::Provides {
+                            service: source(service),
+                            with,
+                        })
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn render_directives(&self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let directives = self.directives();
+        let all = t!("module_all_modules");
+
+        let mut requires: Vec<String> = vec![];
+        let mut exports: Vec<String> = vec![];
+        let mut opens: Vec<String> = vec![];
+        let mut uses: Vec<String> = vec![];
+        let mut provides: Vec<String> = vec![];
+        for directive in directives {
+            match directive {
+                ModuleDirective::Requires {
+                    module,
+                    transitive,
+                    is_static,
+                } => {
+                    let mut flags: Vec<&str> = vec![];
+                    if transitive {
+                        flags.push("transitive");
+                    }
+                    if is_static {
+                        flags.push("static");
+                    }
+                    requires.push(format!("|`{module}`\n|{}", flags.join(", ")));
+                }
+                ModuleDirective::Exports { package, to } => {
+                    let to = if to.is_empty() {
+                        all.to_string()
+                    } else {
+                        to.join(", ")
+                    };
+                    exports.push(format!("|`{package}`\n|{to}"));
+                }
+                ModuleDirective::Opens { package, to } => {
+                    let to = if to.is_empty() {
+                        all.to_string()
+                    } else {
+                        to.join(", ")
+                    };
+                    opens.push(format!("|`{package}`\n|{to}"));
+                }
+                ModuleDirective::Uses { service } => {
+                    uses.push(format!("|`{service}`"));
+                }
+                ModuleDirective::Provides { service, with } => {
+                    provides.push(format!("|`{service}`\n|{}", with.join(", ")));
+                }
+            }
+        }
+
+        let mut sections: Vec<String> = vec![];
+        let mut table = |headline: String, header: String, cols: &str, rows: Vec<String>| {
+            if !rows.is_empty() {
+                let rows = rows.join("\n\n");
+                sections.push(format!(
+                    "{headline}\n\n[cols=\"{cols}\",options=\"header\"]\n|===\n{header}\n\n{rows}\n|===\n"
+                ));
+            }
+        };
+        table(
+            t!("module_exports_headline", nesting = prefix_hashes).to_string(),
+            format!("|{} |{}", t!("module_package"), t!("module_to")),
+            "1,1",
+            exports,
+        );
+        table(
+            t!("module_opens_headline", nesting = prefix_hashes).to_string(),
+            format!("|{} |{}", t!("module_package"), t!("module_to")),
+            "1,1",
+            opens,
+        );
+        table(
+            t!("module_requires_headline", nesting = prefix_hashes).to_string(),
+            format!("|{} |{}", t!("module_module"), t!("module_modifiers")),
+            "1,1",
+            requires,
+        );
+        table(
+            t!("module_uses_headline", nesting = prefix_hashes).to_string(),
+            format!("|{}", t!("module_service")),
+            "1",
+            uses,
+        );
+        table(
+            t!("module_provides_headline", nesting = prefix_hashes).to_string(),
+            format!(
+                "|{} |{}",
+                t!("module_service"),
+                t!("module_implementations")
+            ),
+            "1,1",
+            provides,
+        );
+        sections.join("\n")
+    }
+}
+
+impl<'a> JavaDocable<'a> for Module<'a> {
+    fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let comment = find_documentation(node, ctx);
+        if let Some(comment) = comment {
+            Some(Self {
+                comment,
+                node,
+                context: ctx,
+            })
+        } else {
+            debug!("Found a module declaration but no block comment, skipping");
+            None
+        }
+    }
+
+    fn get_node(&self) -> Node<'_> {
+        self.node
+    }
+
+    fn get_context(&self) -> &'a FileContext {
+        self.context
+    }
+
+    fn get_comment(&self) -> &BlockComment<'a> {
+        &self.comment
+    }
+
+    fn get_name(&self) -> String {
+        let node = self.get_node();
+        let ctx = self.get_context();
+        let name = node.child_by_field_name("name").unwrap();
+        let name = ctx.source_for_range(&name.range());
+        name.to_owned()
+    }
+
+    fn render(&'a self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let name = self.get_name();
+        let headline = if self.is_open() {
+            t!("open_module_headline", nesting = prefix_hashes, name = name)
+        } else {
+            t!("module_headline", nesting = prefix_hashes, name = name)
+        };
+        let content = self.get_comment();
+        let content = format!("{content}");
+        let directives = self.render_directives(level);
+        format!("\n\n{headline}\n\n{content}\n{directives}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        javadoc::{node_to_docable, JavaDocableElement},
+        parser::parse_string,
+    };
+
+    use super::*;
+
+    const JAVADOC: &str = "
+/** The module. */
+open module com.acme.core {
+  requires transitive java.sql;
+  requires static lombok;
+  exports com.acme.api;
+  exports com.acme.spi to com.acme.impl, com.acme.other;
+  uses com.acme.spi.Service;
+  provides com.acme.spi.Service with com.acme.impl.A, com.acme.impl.B;
+}";
+
+    #[test]
+    fn javadoc_comment() {
+        let sourcecode = JAVADOC;
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let filecontext = FileContext::from_str(sourcecode).unwrap();
+
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &filecontext))
+            .collect();
+        assert_eq!(children.len(), 1);
+        let child = children.first().unwrap();
+        match child {
+            JavaDocableElement::Module(child) => {
+                assert_eq!(child.get_name(), "com.acme.core");
+                assert!(child.is_open());
+                assert_eq!(
+                    child.directives(),
+                    vec![
+                        ModuleDirective::Requires {
+                            module: "java.sql".to_owned(),
+                            transitive: true,
+                            is_static: false
+                        },
+                        ModuleDirective::Requires {
+                            module: "lombok".to_owned(),
+                            transitive: false,
+                            is_static: true
+                        },
+                        ModuleDirective::Exports {
+                            package: "com.acme.api".to_owned(),
+                            to: vec![]
+                        },
+                        ModuleDirective::Exports {
+                            package: "com.acme.spi".to_owned(),
+                            to: vec!["com.acme.impl".to_owned(), "com.acme.other".to_owned()]
+                        },
+                        ModuleDirective::Uses {
+                            service: "com.acme.spi.Service".to_owned()
+                        },
+                        ModuleDirective::Provides {
+                            service: "com.acme.spi.Service".to_owned(),
+                            with: vec!["com.acme.impl.A".to_owned(), "com.acme.impl.B".to_owned()]
+                        },
+                    ]
+                );
+            }
+            _ => panic!("Got something else than a module????"),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use rust_i18n::t;
+use tracing::debug;
+use tree_sitter::Node;
+
+use super::{
+    comment::{find_block_comment, BlockComment},
+    prefix_hashes, FileContext, JavaDocable,
+};
+
+#[derive(Debug)]
+pub struct Package<'a> {
+    comment: BlockComment<'a>,
+    node: Node<'a>,
+    context: &'a FileContext,
+}
+
+impl<'a> JavaDocable<'a> for Package<'a> {
+    fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        // comments in front of the package of other files are most likely license headers
+        if ctx.declares_types() {
+            debug!("Found a package declaration in a file declaring types, skipping");
+            return None;
+        }
+        let comment = find_block_comment(node, ctx);
+        if let Some(comment) = comment {
+            Some(Self {
+                comment,
+                node,
+                context: ctx,
+            })
+        } else {
+            debug!("Found a package declaration but no block comment, skipping");
+            None
+        }
+    }
+
+    fn get_node(&self) -> Node<'_> {
+        self.node
+    }
+
+    fn get_context(&self) -> &'a FileContext {
+        self.context
+    }
+
+    fn get_comment(&self) -> &BlockComment<'a> {
+        &self.comment
+    }
+
+    fn get_name(&self) -> String {
+        package_name(self.get_node(), self.get_context())
+    }
+
+    fn render(&'a self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let name = self.get_name();
+        let headline = t!("package_headline", nesting = prefix_hashes, name = name);
+        let content = self.get_comment();
+        let content = format!("{content}");
+        format!("\n\n{headline}\n\n{content}")
+    }
+}
+
+/// Extracts the declared package name from a `package_declaration` node
+pub fn package_name(node: Node<'_>, ctx: &FileContext) -> String {
+    let mut cursor = node.walk();
+    let name = node
+        .named_children(&mut cursor)
+        .find(|child| matches!(child.grammar_name(), "identifier" | "scoped_identifier"))
+        .unwrap();
+    ctx.source_for_range(&name.range()).to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::parser::parse_string;
+
+    use super::*;
+
+    fn package(source: &str) -> Option<String> {
+        let tree = parse_string(source).unwrap();
+        let ctx = FileContext::from_str(source).unwrap();
+        let mut cursor = tree.root_node().walk();
+        let node = tree
+            .root_node()
+            .named_children(&mut cursor)
+            .find(|node| node.grammar_name() == "package_declaration")
+            .unwrap();
+        Package::new(&ctx, node).map(|package| package.get_name())
+    }
+
+    #[test]
+    fn documents_package_info() {
+        let source = "/** The acme package. */\npackage com.acme;\n";
+        assert_eq!(package(source).as_deref(), Some("com.acme"));
+    }
+
+    #[test]
+    fn skips_license_headers() {
+        let source = "/** Copyright ACME */\npackage com.acme;\n\n/** Foo. */\nclass Foo {}\n";
+        assert_eq!(package(source), None);
+    }
+}
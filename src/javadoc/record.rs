@@ -0,0 +1,245 @@
+use rust_i18n::t;
+use tracing::{debug, instrument};
+use tree_sitter::Node;
+
+use crate::javadoc::{
+    annotation_type::AnnotationType, class::Class, constructor::Constructor, enumeration::Enum,
+    field::Field, interface::Interface, method::Method, prefix_hashes,
+};
+
+use super::{
+    anchor::{render_anchor, tagged_region},
+    comment::{find_documentation, BlockComment},
+    declaration::render_declaration,
+    node_to_docable,
+    signature::parameters,
+    FileContext, JavaDocable, JavaDocableElement,
+};
+
+#[derive(Debug)]
+pub struct Record<'a> {
+    comment: BlockComment<'a>,
+    node: Node<'a>,
+    context: &'a FileContext,
+    children: Vec<JavaDocableElement<'a>>,
+}
+
+impl<'a> Record<'a> {
+    pub fn get_children(&self) -> &[JavaDocableElement<'a>] {
+        &self.children
+    }
+
+    /// Renders the record components as a definition list, described by the matching `@param` tags
+    fn render_components(&self) -> String {
+        let node = self.get_node();
+        let ctx = self.get_context();
+        let javadoc = self.get_comment().rendered_javadoc();
+
+        let components: Vec<String> = parameters(node, ctx)
+            .iter()
+            .map(|param| {
+                let paramtype = &param.paramtype;
+                let name = &param.name;
+                let description = javadoc.param(name).unwrap_or_default();
+                format!("`{paramtype} {name}`:: {description}")
+            })
+            .collect();
+        components.join("\n")
+    }
+}
+
+impl<'a> JavaDocable<'a> for Record<'a> {
+    #[instrument(skip_all)]
+    fn new(ctx: &'a FileContext, node: Node<'a>) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let comment = find_documentation(node, ctx);
+        let mut cursor = node.walk();
+        let body = node.child_by_field_name("body").unwrap();
+
+        let mut children: Vec<JavaDocableElement> = vec![];
+        for child in body.children(&mut cursor) {
+            if let Some(docable) = node_to_docable(child, ctx) {
+                children.push(docable);
+            }
+        }
+
+        if let Some(comment) = comment {
+            Some(Self {
+                comment,
+                node,
+                context: ctx,
+                children,
+            })
+        } else {
+            debug!("Found a record but no block comment, skipping");
+            None
+        }
+    }
+
+    fn get_node(&self) -> Node<'_> {
+        self.node
+    }
+
+    fn get_context(&self) -> &'a FileContext {
+        self.context
+    }
+
+    fn get_comment(&self) -> &BlockComment<'a> {
+        &self.comment
+    }
+
+    fn get_name(&self) -> String {
+        let node = self.get_node();
+        let name = node.child_by_field_name("name").unwrap();
+        let ctx = self.context;
+        let name = ctx.source_for_range(&name.range());
+        name.to_owned()
+    }
+
+    fn render(&'a self, level: u8) -> String {
+        let prefix_hashes = prefix_hashes(level);
+        let name = self.get_name();
+        let anchor = render_anchor(self.get_node(), self.get_context());
+        let headline = format!("{anchor}{prefix_hashes} {name}");
+        let declaration = render_declaration(self.get_node(), self.get_context());
+        // the components are listed on their own
+        let content = self.get_comment().render_without(&["param"]);
+
+        let mut fields: Vec<&Field<'a>> = vec![];
+        let mut methods: Vec<&Method<'a>> = vec![];
+        let mut classes: Vec<&Class<'a>> = vec![];
+        let mut interfaces: Vec<&Interface<'a>> = vec![];
+        let mut enums: Vec<&Enum<'a>> = vec![];
+        let mut records: Vec<&Record<'a>> = vec![];
+        let mut annotations: Vec<&AnnotationType<'a>> = vec![];
+        let mut constructors: Vec<&Constructor<'a>> = vec![];
+
+        // collect and group all children
+        for child in &self.children {
+            match child {
+                JavaDocableElement::Class(class) => {
+                    classes.push(class);
+                }
+                JavaDocableElement::Enum(en) => {
+                    enums.push(en);
+                }
+                JavaDocableElement::Record(record) => {
+                    records.push(record);
+                }
+                JavaDocableElement::Field(field) => {
+                    fields.push(field);
+                }
+                JavaDocableElement::Method(method) => {
+                    methods.push(method);
+                }
+                JavaDocableElement::Constructor(constructor) => {
+                    constructors.push(constructor);
+                }
+                JavaDocableElement::Interface(interface) => {
+                    interfaces.push(interface);
+                }
+                JavaDocableElement::EnumConstant(_) => (),
+                JavaDocableElement::AnnotationType(annotation) => {
+                    annotations.push(annotation);
+                }
+                JavaDocableElement::AnnotationElement(_) => (),
+                JavaDocableElement::Package(_) => (),
+                JavaDocableElement::Module(_) => (),
+            }
+        }
+        let components_headline = t!("record_component_headline", nesting = prefix_hashes);
+        let fields_headline = t!("field_headline", nesting = prefix_hashes);
+        let methods_headline = t!("method_headline", nesting = prefix_hashes);
+        let classes_headline = t!("class_headline", nesting = prefix_hashes);
+        let constructors_headline = t!("constructor_headline", nesting = prefix_hashes);
+
+        //stringify all of the children with increased nesting levels
+        let components = self.render_components();
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        let fields = fields.join("\n");
+        let methods: Vec<String> = methods
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        let methods = methods.join("\n");
+        let mut classes: Vec<String> = classes
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        classes.extend(interfaces.iter().map(|&child| child.render(level + 1)));
+        classes.extend(enums.iter().map(|&child| child.render(level + 1)));
+        classes.extend(records.iter().map(|&child| child.render(level + 1)));
+        classes.extend(annotations.iter().map(|&child| child.render(level + 1)));
+        let classes = classes.join("\n");
+        let constructors: Vec<String> = constructors
+            .iter()
+            .map(|&child| child.render(level + 1))
+            .collect();
+        let constructors = constructors.join("\n");
+
+        let rendered = format!("\n\n{headline}\n\n{declaration}{content}{components_headline}\n\n{components}\n\n{constructors_headline}\n\n{constructors}{fields_headline}\n\n{fields}{methods_headline}\n\n{methods}{classes_headline}\n\n{classes}");
+        tagged_region(self.get_node(), self.get_context(), rendered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::parser::parse_string;
+
+    use super::*;
+
+    const JAVADOC: &str = "
+/**
+ * A point.
+ * @param x the x coordinate
+ * @param y the y coordinate
+ */
+record Point(int x, int y) {
+  /** validates the point */
+  Point {
+    if (x < 0) throw new IllegalArgumentException();
+  }
+
+  /** distance to the origin */
+  double length() {
+    return Math.sqrt(x * x + y * y);
+  }
+}";
+
+    #[test]
+    fn javadoc_comment() {
+        let sourcecode = JAVADOC;
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let filecontext = FileContext::from_str(sourcecode).unwrap();
+
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &filecontext))
+            .collect();
+        assert_eq!(children.len(), 1);
+        let child = children.first().unwrap();
+        match child {
+            JavaDocableElement::Record(child) => {
+                assert_eq!(child.get_name(), "Point");
+                assert_eq!(child.children.len(), 2);
+                assert_eq!(
+                    child.render_components(),
+                    "`int x`:: the x coordinate\n`int y`:: the y coordinate"
+                );
+                let rendered = child.render(0);
+                assert!(rendered.contains("A point.\n"));
+                assert!(!rendered.contains("param::"));
+            }
+            _ => panic!("Got something else than a record????"),
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use rust_i18n::t;
+use tree_sitter::Node;
+
+use super::{declaration::normalize_whitespace, tags::Javadoc, FileContext};
+
+/// Block tags laid out by the parameter, return and throws sections instead of the comment text
+pub const SIGNATURE_TAGS: [&str; 3] = ["param", "return", "throws"];
+
+/// A single declared parameter of a method or constructor
+#[derive(Debug, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub paramtype: String,
+}
+
+/// Collects the declared parameters of a method, constructor or record header
+pub fn parameters(node: Node<'_>, ctx: &FileContext) -> Vec<Parameter> {
+    let Some(params) = node.child_by_field_name("parameters") else {
+        return vec![];
+    };
+    let mut cursor = params.walk();
+    params
+        .named_children(&mut cursor)
+        .filter_map(|param| match param.grammar_name() {
+            "formal_parameter" => {
+                let name = param.child_by_field_name("name")?;
+                let paramtype = param.child_by_field_name("type")?;
+                let mut paramtype = ctx.source_for_range(&paramtype.range()).to_owned();
+                // C-style array declarations like `int a[]`
+                if let Some(dimensions) = param.child_by_field_name("dimensions") {
+                    paramtype.push_str(ctx.source_for_range(&dimensions.range()));
+                }
+                Some(Parameter {
+                    name: ctx.source_for_range(&name.range()).to_owned(),
+                    paramtype,
+                })
+            }
+            "spread_parameter" => {
+                let mut cursor = param.walk();
+                let children: Vec<Node> = param.named_children(&mut cursor).collect();
+                let declarator = children
+                    .iter()
+                    .find(|child| child.grammar_name() == "variable_declarator")?;
+                let name = declarator.child_by_field_name("name")?;
+                let paramtype = children.iter().find(|child| {
+                    !matches!(child.grammar_name(), "modifiers" | "variable_declarator")
+                })?;
+                let paramtype = ctx.source_for_range(&paramtype.range());
+                Some(Parameter {
+                    name: ctx.source_for_range(&name.range()).to_owned(),
+                    paramtype: format!("{paramtype}..."),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Collects the exception types of the `throws` clause
+pub fn thrown_types(node: Node<'_>, ctx: &FileContext) -> Vec<String> {
+    let mut cursor = node.walk();
+    let Some(throws) = node
+        .children(&mut cursor)
+        .find(|child| child.grammar_name() == "throws")
+    else {
+        return vec![];
+    };
+    let mut cursor = throws.walk();
+    let thrown = throws
+        .named_children(&mut cursor)
+        .map(|exception| ctx.source_for_range(&exception.range()).to_owned())
+        .collect();
+    thrown
+}
+
+/// Collects the declared type parameters of a type, method or constructor, like
+/// `T extends Comparable<T>`
+pub fn type_parameters(node: Node<'_>, ctx: &FileContext) -> Vec<String> {
+    let Some(params) = node.child_by_field_name("type_parameters") else {
+        return vec![];
+    };
+    let mut cursor = params.walk();
+    let params = params
+        .named_children(&mut cursor)
+        .filter(|param| param.grammar_name() == "type_parameter")
+        .map(|param| normalize_whitespace(ctx.source_for_range(&param.range())))
+        .collect();
+    params
+}
+
+/// Types named in the `extends`, `implements` and `permits` clauses of a type declaration
+#[derive(Debug, Default, PartialEq)]
+pub struct Supertypes {
+    pub extends: Vec<String>,
+    pub implements: Vec<String>,
+    pub permits: Vec<String>,
+}
+
+/// Collects the supertypes of a type declaration, interfaces extend rather than implement theirs
+pub fn supertypes(node: Node<'_>, ctx: &FileContext) -> Supertypes {
+    let mut supertypes = Supertypes::default();
+    let mut cursor = node.walk();
+    for clause in node.named_children(&mut cursor) {
+        let types = match clause.grammar_name() {
+            "superclass" | "extends_interfaces" => &mut supertypes.extends,
+            "super_interfaces" => &mut supertypes.implements,
+            "permits" => &mut supertypes.permits,
+            _ => continue,
+        };
+        let mut cursor = clause.walk();
+        for child in clause.named_children(&mut cursor) {
+            // all clauses but `extends` of classes hold a list of types
+            let mut cursor = child.walk();
+            let listed: Vec<Node> = match child.grammar_name() {
+                "type_list" => child.named_children(&mut cursor).collect(),
+                _ => vec![child],
+            };
+            types.extend(
+                listed
+                    .iter()
+                    .map(|listed| normalize_whitespace(ctx.source_for_range(&listed.range()))),
+            );
+        }
+    }
+    supertypes
+}
+
+/// Renders a table of all type parameters with their `@param <T>` description
+pub fn render_type_parameters(node: Node<'_>, ctx: &FileContext, javadoc: &Javadoc) -> String {
+    let rows: Vec<String> = type_parameters(node, ctx)
+        .iter()
+        .filter_map(|param| {
+            // annotations may precede the name of the type variable
+            let name = param
+                .split_whitespace()
+                .find(|word| !word.starts_with('@'))?;
+            let description = javadoc.param(&format!("<{name}>")).unwrap_or_default();
+            let description = escape_cell(description);
+            Some(format!("|`{param}`\n|{description}"))
+        })
+        .collect();
+    let header = format!("|{} |{}", t!("parameter_name"), t!("parameter_description"));
+    render_table(&t!("type_parameters_title"), &header, "1,3a", &rows)
+}
+
+/// Renders a table of all parameters with their declared type and `@param` description
+pub fn render_parameters(node: Node<'_>, ctx: &FileContext, javadoc: &Javadoc) -> String {
+    let rows: Vec<String> = parameters(node, ctx)
+        .iter()
+        .map(|param| {
+            let name = &param.name;
+            let paramtype = &param.paramtype;
+            let description = javadoc.param(name).unwrap_or_default();
+            let description = escape_cell(description);
+            format!("|`{name}`\n|`{paramtype}`\n|{description}")
+        })
+        .collect();
+    let header = format!(
+        "|{} |{} |{}",
+        t!("parameter_name"),
+        t!("parameter_type"),
+        t!("parameter_description")
+    );
+    render_table(&t!("parameters_title"), &header, "1,1,3a", &rows)
+}
+
+/// The declared return type together with the `@return` description, if any. There is none for
+/// constructors and `void` methods without a `@return` tag.
+pub fn documented_returns<'a>(
+    node: Node<'_>,
+    ctx: &FileContext,
+    javadoc: &'a Javadoc,
+) -> Option<(String, Option<&'a str>)> {
+    let returntype = node.child_by_field_name("type")?;
+    let mut returntype = ctx.source_for_range(&returntype.range()).to_owned();
+    if let Some(dimensions) = node.child_by_field_name("dimensions") {
+        returntype.push_str(ctx.source_for_range(&dimensions.range()));
+    }
+    let description = javadoc.returns();
+    if returntype == "void" && description.is_none() {
+        return None;
+    }
+    Some((returntype, description))
+}
+
+/// Renders the declared return type together with the `@return` description.
+/// Nothing is rendered for constructors and `void` methods without a `@return` tag.
+pub fn render_returns(node: Node<'_>, ctx: &FileContext, javadoc: &Javadoc) -> String {
+    let Some((returntype, description)) = documented_returns(node, ctx, javadoc) else {
+        return String::new();
+    };
+    let title = t!("returns_title");
+    match description {
+        Some(description) => format!("\n.{title}\n`{returntype}`: {description}\n\n"),
+        None => format!("\n.{title}\n`{returntype}`\n\n"),
+    }
+}
+
+/// All exceptions from the `throws` clause and the `@throws` tags, with their description
+pub fn documented_throws<'a>(
+    node: Node<'_>,
+    ctx: &FileContext,
+    javadoc: &'a Javadoc,
+) -> Vec<(String, &'a str)> {
+    let mut documented = javadoc.throws();
+    let mut exceptions: Vec<(String, &str)> = vec![];
+    for exception in thrown_types(node, ctx) {
+        let position = documented
+            .iter()
+            .position(|(tag, _)| same_type(tag, &exception));
+        let description = match position {
+            Some(position) => documented.remove(position).1,
+            None => "",
+        };
+        exceptions.push((exception, description));
+    }
+    // unchecked exceptions are usually only documented, not declared
+    for (exception, description) in documented {
+        exceptions.push((exception.to_owned(), description));
+    }
+    exceptions
+}
+
+/// Renders a table of all exceptions from the `throws` clause and the `@throws` tags
+pub fn render_throws(node: Node<'_>, ctx: &FileContext, javadoc: &Javadoc) -> String {
+    let rows: Vec<String> = documented_throws(node, ctx, javadoc)
+        .iter()
+        .map(|(exception, description)| {
+            let description = escape_cell(description);
+            format!("|`{exception}`\n|{description}")
+        })
+        .collect();
+    let header = format!("|{} |{}", t!("parameter_type"), t!("parameter_description"));
+    render_table(&t!("throws_title"), &header, "1,3a", &rows)
+}
+
+fn render_table(title: &str, header: &str, cols: &str, rows: &[String]) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+    let rows = rows.join("\n\n");
+    format!("\n.{title}\n[cols=\"{cols}\",options=\"header\"]\n|===\n{header}\n\n{rows}\n|===\n")
+}
+
+/// Escapes the cell separators within the content of an asciidoc table cell
+pub fn escape_cell(content: &str) -> String {
+    content.replace('|', "\\|")
+}
+
+/// Escapes the cell separators within the content of a markdown table cell, joining its lines as
+/// markdown cells can't span several lines
+pub fn escape_markdown_cell(content: &str) -> String {
+    let paragraphs: Vec<String> = content
+        .trim()
+        .split("\n\n")
+        .map(|paragraph| {
+            let lines: Vec<&str> = paragraph.lines().map(|line| line.trim()).collect();
+            lines.join(" ")
+        })
+        .collect();
+    escape_cell(&paragraphs.join("<br><br>"))
+}
+
+/// Compares two type names, ignoring whether they are qualified
+pub(super) fn same_type(a: &str, b: &str) -> bool {
+    let simple = |name: &str| name.rsplit('.').next().unwrap_or(name).to_owned();
+    a == b || simple(a) == simple(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::parser::parse_string;
+
+    use super::*;
+
+    const SOURCE: &str = "
+class A {
+  int[] foo(final int a, @Nullable String b[], List<T> c, String... rest)
+      throws IOException, java.lang.IllegalStateException {}
+}";
+
+    const JAVADOC: &[&str] = &[
+        "@param a the a",
+        "@param rest everything | else",
+        "@return the result",
+        "@throws IllegalStateException when in a bad mood",
+        "@throws NullPointerException never",
+    ];
+
+    fn with_method(f: impl Fn(Node<'_>, &FileContext)) {
+        let tree = parse_string(SOURCE).unwrap();
+        let ctx = FileContext::from_str(SOURCE).unwrap();
+        let class = tree.root_node().named_child(0).unwrap();
+        let body = class.child_by_field_name("body").unwrap();
+        let method = body.named_child(0).unwrap();
+        f(method, &ctx);
+    }
+
+    #[test]
+    fn collects_parameters() {
+        with_method(|method, ctx| {
+            let params: Vec<(String, String)> = parameters(method, ctx)
+                .into_iter()
+                .map(|param| (param.paramtype, param.name))
+                .collect();
+            assert_eq!(
+                params,
+                vec![
+                    ("int".to_owned(), "a".to_owned()),
+                    ("String[]".to_owned(), "b".to_owned()),
+                    ("List<T>".to_owned(), "c".to_owned()),
+                    ("String...".to_owned(), "rest".to_owned()),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn renders_parameters() {
+        with_method(|method, ctx| {
+            let javadoc = Javadoc::parse(JAVADOC);
+            let result = render_parameters(method, ctx, &javadoc);
+            assert!(result.contains("|`a`\n|`int`\n|the a"));
+            assert!(result.contains("|`b`\n|`String[]`\n|\n"));
+            assert!(result.contains("|`rest`\n|`String...`\n|everything \\| else"));
+        });
+    }
+
+    #[test]
+    fn renders_returns() {
+        with_method(|method, ctx| {
+            let javadoc = Javadoc::parse(JAVADOC);
+            let result = render_returns(method, ctx, &javadoc);
+            assert_eq!(result, "\n.Returns\n`int[]`: the result\n\n");
+        });
+    }
+
+    #[test]
+    fn renders_throws() {
+        with_method(|method, ctx| {
+            let javadoc = Javadoc::parse(JAVADOC);
+            let result = render_throws(method, ctx, &javadoc);
+            let expected = "|`IOException`\n|\n\n|`java.lang.IllegalStateException`\n|when in a bad mood\n\n|`NullPointerException`\n|never\n|===";
+            assert!(result.ends_with(&format!("{expected}\n")));
+        });
+    }
+
+    #[test]
+    fn renders_type_parameters() {
+        let source = "
+class A {
+  <T extends Comparable<T>, @NonNull U> T max(List<T> values, U other) {}
+}";
+        let tree = parse_string(source).unwrap();
+        let ctx = FileContext::from_str(source).unwrap();
+        let class = tree.root_node().named_child(0).unwrap();
+        let method = class
+            .child_by_field_name("body")
+            .unwrap()
+            .named_child(0)
+            .unwrap();
+        let javadoc = Javadoc::parse(["@param <T> the element type", "@param values the values"]);
+        assert_eq!(
+            render_type_parameters(method, &ctx, &javadoc),
+            "\n.Type Parameters\n[cols=\"1,3a\",options=\"header\"]\n|===\n|Name |Description\n\n\
+             |`T extends Comparable<T>`\n|the element type\n\n|`@NonNull U`\n|\n|===\n"
+        );
+    }
+
+    #[test]
+    fn collects_type_parameters_and_supertypes() {
+        let source = "
+class Box<T extends Comparable<T>, U> extends Base<T> implements Closeable, Iterable<U>
+    permits Small {}
+interface Shape extends Sized, Named {}";
+        let tree = parse_string(source).unwrap();
+        let ctx = FileContext::from_str(source).unwrap();
+        let class = tree.root_node().named_child(0).unwrap();
+        assert_eq!(
+            type_parameters(class, &ctx),
+            vec!["T extends Comparable<T>", "U"]
+        );
+        assert_eq!(
+            supertypes(class, &ctx),
+            Supertypes {
+                extends: vec!["Base<T>".to_owned()],
+                implements: vec!["Closeable".to_owned(), "Iterable<U>".to_owned()],
+                permits: vec!["Small".to_owned()],
+            }
+        );
+        let interface = tree.root_node().named_child(1).unwrap();
+        assert_eq!(supertypes(interface, &ctx).extends, vec!["Sized", "Named"]);
+    }
+}
@@ -0,0 +1,577 @@
+//! Rendering of `{@snippet}` tags as asciidoc source listings, applying the markup comments
+//! `@highlight`, `@replace` and `@link` as well as the regions marked by `@start` and `@end`.
+//! Markdown pages get plain fenced code blocks instead, as those can't hold any markup.
+
+use std::{fs::read_to_string, path::PathBuf, sync::LazyLock};
+
+use regex::Regex;
+use tracing::{debug, trace, warn};
+use tree_sitter::Node;
+
+use super::{
+    inline::{closing_brace, render_link},
+    FileContext, TextFormat,
+};
+
+/// Replaces every `{@snippet}` tag of `text` by a placeholder, so the conversion of the
+/// surrounding markup doesn't touch the snippet body. Returns the text and the replaced tags.
+pub fn protect_snippets(text: &str) -> (String, Vec<String>) {
+    let mut result = String::new();
+    let mut snippets: Vec<String> = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find("{@snippet") {
+        result.push_str(&rest[..start]);
+        let tag = &rest[start..];
+        let Some(end) = closing_brace(tag) else {
+            break;
+        };
+        result.push_str(&placeholder(snippets.len()));
+        snippets.push(tag[..=end].to_owned());
+        rest = &tag[end + 1..];
+    }
+    result.push_str(rest);
+    (result, snippets)
+}
+
+/// Puts the tags replaced by [protect_snippets] back in place
+pub fn restore_snippets(text: &str, snippets: &[String]) -> String {
+    let mut text = text.to_owned();
+    for (index, snippet) in snippets.iter().enumerate() {
+        text = text.replacen(&placeholder(index), snippet, 1);
+    }
+    text
+}
+
+fn placeholder(index: usize) -> String {
+    format!("{{@snippet-{index}}}")
+}
+
+/// Parses attributes like `file=Foo.java region="main part"`
+fn parse_attributes(attributes: &str) -> Vec<(String, String)> {
+    let mut result: Vec<(String, String)> = vec![];
+    let mut rest = attributes.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_owned();
+        rest = rest[name_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let after = &after[1..];
+                        let end = after.find(quote).unwrap_or(after.len());
+                        (&after[..end], after.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining.trim_start();
+                value.to_owned()
+            }
+            None => String::new(),
+        };
+        if !name.is_empty() {
+            result.push((name, value));
+        }
+    }
+    result
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Splits the content of a snippet tag into its attributes and the inline body, if any
+fn split_body(content: &str) -> (&str, Option<&str>) {
+    let mut quote: Option<char> = None;
+    for (index, c) in content.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, ':') => {
+                let body = &content[index + 1..];
+                // the body starts on the line after the colon
+                let body = match body.split_once('\n') {
+                    Some((_, body)) => body,
+                    None => body,
+                };
+                return (&content[..index], Some(body));
+            }
+            _ => (),
+        }
+    }
+    (content, None)
+}
+
+/// Start of a markup comment like `// @highlight ...`
+static MARKUP_COMMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"//\s*@(start|end|highlight|replace|link)\b").unwrap());
+
+/// A markup tag within a snippet comment, like `@highlight substring="foo" type=italic`
+#[derive(Debug, Clone)]
+struct Markup {
+    name: String,
+    attributes: Vec<(String, String)>,
+}
+
+impl Markup {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        attribute(&self.attributes, name)
+    }
+
+    /// Whether the markup applies to a region instead of a single line
+    fn is_regional(&self) -> bool {
+        self.attribute("region").is_some()
+    }
+}
+
+/// Splits a line into code and the markup tags of a trailing `// @...` comment. The flag tells
+/// whether the markup applies to the next line instead, as marked by a trailing colon.
+fn split_markup(line: &str) -> (&str, Vec<Markup>, bool) {
+    let Some(found) = MARKUP_COMMENT.find(line) else {
+        return (line, vec![], false);
+    };
+    let code = &line[..found.start()];
+    let comment = line[found.start() + 2..].trim();
+    let (comment, next_line) = match comment.strip_suffix(':') {
+        Some(comment) => (comment, true),
+        None => (comment, false),
+    };
+
+    let mut markups: Vec<Markup> = vec![];
+    let mut quote: Option<char> = None;
+    let mut start: Option<usize> = None;
+    let mut boundaries: Vec<usize> = vec![];
+    for (index, c) in comment.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, '@') => boundaries.push(index),
+            _ => (),
+        }
+    }
+    boundaries.push(comment.len());
+    for boundary in boundaries {
+        if let Some(start) = start {
+            let markup = &comment[start + 1..boundary];
+            let (name, attributes) = markup
+                .split_once(char::is_whitespace)
+                .unwrap_or((markup, ""));
+            markups.push(Markup {
+                name: name.to_owned(),
+                attributes: parse_attributes(attributes),
+            });
+        }
+        start = Some(boundary);
+    }
+    (code.trim_end(), markups, next_line)
+}
+
+/// Byte ranges of `text` selected by the `substring` or `regex` attribute of a markup tag, or
+/// the whole line without its indentation if there is neither
+fn matches(markup: &Markup, text: &str) -> Vec<(usize, usize)> {
+    if let Some(substring) = markup.attribute("substring") {
+        if substring.is_empty() {
+            return vec![];
+        }
+        return text
+            .match_indices(substring)
+            .map(|(start, found)| (start, start + found.len()))
+            .collect();
+    }
+    if let Some(pattern) = markup.attribute("regex") {
+        return match Regex::new(pattern) {
+            Ok(regex) => regex
+                .find_iter(text)
+                .map(|found| (found.start(), found.end()))
+                .filter(|(start, end)| start < end)
+                .collect(),
+            Err(error) => {
+                warn!("Invalid regex {pattern:?} in snippet: {error}");
+                vec![]
+            }
+        };
+    }
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len();
+    if start < end {
+        vec![(start, end)]
+    } else {
+        vec![]
+    }
+}
+
+fn apply_replace(markup: &Markup, text: &str) -> String {
+    let replacement = markup.attribute("replacement").unwrap_or_default();
+    if let Some(substring) = markup.attribute("substring") {
+        if substring.is_empty() {
+            return text.to_owned();
+        }
+        return text.replace(substring, replacement);
+    }
+    if let Some(pattern) = markup.attribute("regex") {
+        return match Regex::new(pattern) {
+            Ok(regex) => regex.replace_all(text, replacement).into_owned(),
+            Err(error) => {
+                warn!("Invalid regex {pattern:?} in snippet: {error}");
+                text.to_owned()
+            }
+        };
+    }
+    replacement.to_owned()
+}
+
+/// A highlighted or linked part of a line
+#[derive(Debug)]
+struct Span {
+    start: usize,
+    end: usize,
+    open: &'static str,
+    close: &'static str,
+    /// replaces the marked text, used for links
+    replacement: Option<String>,
+}
+
+/// A line of the snippet after replacements, with the parts to highlight or link
+#[derive(Debug)]
+struct Line {
+    text: String,
+    spans: Vec<Span>,
+}
+
+/// Applies the markup tags of a snippet body and selects the lines of `region`, if given
+fn process_body(
+    body: &str,
+    region: Option<&str>,
+    scope: Option<Node<'_>>,
+    ctx: &FileContext,
+) -> Vec<Line> {
+    let mut lines: Vec<Line> = vec![];
+    // open regions: name (empty for anonymous ones) and the markup applied within
+    let mut regions: Vec<(String, Option<Markup>)> = vec![];
+    let mut pending: Vec<Markup> = vec![];
+
+    for line in body.lines() {
+        let (code, markups, next_line) = split_markup(line);
+        let mut local: Vec<Markup> = std::mem::take(&mut pending);
+        for markup in &markups {
+            match markup.name.as_str() {
+                "start" => {
+                    let name = markup.attribute("region").unwrap_or_default();
+                    regions.push((name.to_owned(), None));
+                }
+                "end" => match markup.attribute("region") {
+                    Some(name) => {
+                        if let Some(position) = regions.iter().rposition(|(open, _)| open == name) {
+                            regions.remove(position);
+                        }
+                    }
+                    None => {
+                        regions.pop();
+                    }
+                },
+                _ if markup.is_regional() => {
+                    let name = markup.attribute("region").unwrap_or_default();
+                    regions.push((name.to_owned(), Some(markup.clone())));
+                }
+                _ if next_line => pending.push(markup.clone()),
+                _ => local.push(markup.clone()),
+            }
+        }
+        // lines holding nothing but markup are not part of the snippet
+        if !markups.is_empty() && code.trim().is_empty() {
+            continue;
+        }
+        let selected = match region {
+            Some(region) => regions.iter().any(|(name, _)| name == region),
+            None => true,
+        };
+        if !selected {
+            continue;
+        }
+
+        let active: Vec<&Markup> = regions
+            .iter()
+            .filter_map(|(_, markup)| markup.as_ref())
+            .chain(local.iter())
+            .collect();
+        let mut text = code.to_owned();
+        for markup in active.iter().filter(|markup| markup.name == "replace") {
+            text = apply_replace(markup, &text);
+        }
+        let mut spans: Vec<Span> = vec![];
+        for markup in &active {
+            let (open, close) = match markup.name.as_str() {
+                "highlight" => match markup.attribute("type").unwrap_or("bold") {
+                    "italic" => ("__", "__"),
+                    "highlighted" => ("##", "##"),
+                    _ => ("**", "**"),
+                },
+                "link" => ("", ""),
+                _ => continue,
+            };
+            for (start, end) in matches(markup, &text) {
+                // overlapping markup can't be expressed in asciidoc, the first one wins
+                if spans
+                    .iter()
+                    .any(|span| start < span.end && span.start < end)
+                {
+                    continue;
+                }
+                let span = if markup.name == "link" {
+                    let target = markup.attribute("target").unwrap_or_default();
+                    let label = &text[start..end];
+                    let link = render_link(&format!("{target} {label}"), true, scope, ctx);
+                    if link == label {
+                        debug!("Could not link snippet text {label:?} to {target:?}");
+                        continue;
+                    }
+                    Span {
+                        start,
+                        end,
+                        open,
+                        close,
+                        replacement: Some(link),
+                    }
+                } else {
+                    Span {
+                        start,
+                        end,
+                        open,
+                        close,
+                        replacement: None,
+                    }
+                };
+                spans.push(span);
+            }
+        }
+        spans.sort_by_key(|span| span.start);
+        lines.push(Line { text, spans });
+    }
+    lines
+}
+
+/// Renders the processed lines without their common indentation, with their highlights and links
+/// unless `markup` is false. Returns the listing content and whether any markup has been applied,
+/// in which case all code is passed through as is, so only the markup is substituted.
+fn render_lines(lines: &[Line], markup: bool) -> (String, bool) {
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.text.trim().is_empty())
+        .map(|line| line.text.len() - line.text.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let has_markup = markup && lines.iter().any(|line| !line.spans.is_empty());
+    let code = |text: &str| match has_markup {
+        true => passthrough(text),
+        false => text.to_owned(),
+    };
+    let rendered: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            let text = line.text.get(indentation..).unwrap_or("");
+            let mut result = String::new();
+            let mut position = 0;
+            for span in line.spans.iter().filter(|_| has_markup) {
+                let start = span.start.saturating_sub(indentation);
+                let end = span.end.saturating_sub(indentation);
+                result.push_str(&code(&text[position..start]));
+                result.push_str(span.open);
+                match &span.replacement {
+                    Some(replacement) => result.push_str(replacement),
+                    None => result.push_str(&code(&text[start..end])),
+                }
+                result.push_str(span.close);
+                position = end;
+            }
+            result.push_str(&code(text[position..].trim_end()));
+            result.trim_end().to_owned()
+        })
+        .collect();
+    (rendered.join("\n"), has_markup)
+}
+
+/// Code within a listing with substitutions, protected from all of them but special characters
+fn passthrough(text: &str) -> String {
+    if text.trim().is_empty() {
+        return text.to_owned();
+    }
+    format!("pass:c[{}]", text.replace(']', "\\]"))
+}
+
+/// Looks up an external snippet file in the snippet directories
+fn read_snippet_file(file: &str, ctx: &FileContext) -> Option<String> {
+    ctx.snippet_dirs().iter().find_map(|dir| {
+        let path: PathBuf = dir.join(file);
+        trace!("Looking for snippet file at {path:?}");
+        read_to_string(&path).ok()
+    })
+}
+
+/// Renders the content of a `{@snippet ...}` tag as a source listing
+pub fn render_snippet(content: &str, scope: Option<Node<'_>>, ctx: &FileContext) -> String {
+    let (attributes, body) = split_body(content);
+    let attributes = parse_attributes(attributes);
+    let file = attribute(&attributes, "file")
+        .map(|file| file.to_owned())
+        .or_else(|| {
+            attribute(&attributes, "class").map(|class| format!("{}.java", class.replace('.', "/")))
+        });
+
+    let external = file.as_deref().and_then(|file| {
+        let content = read_snippet_file(file, ctx);
+        if content.is_none() {
+            warn!(
+                "Snippet file {file:?} not found in {:?}",
+                ctx.snippet_dirs()
+            );
+        }
+        content
+    });
+    // external files take precedence over the inline body of hybrid snippets
+    let Some(body) = external.as_deref().or(body) else {
+        return String::new();
+    };
+
+    let language = attribute(&attributes, "lang")
+        .map(|language| language.to_owned())
+        .or_else(|| {
+            let file = file.as_deref()?;
+            let (_, extension) = file.rsplit_once('.')?;
+            Some(extension.to_owned())
+        })
+        .unwrap_or_else(|| "java".to_owned());
+    let region = attribute(&attributes, "region");
+    let lines = process_body(body, region, scope, ctx);
+    if ctx.text_format() == TextFormat::Markdown {
+        // fenced code blocks can't hold any markup
+        let (listing, _) = render_lines(&lines, false);
+        return format!("\n\n```{language}\n{listing}\n```\n\n");
+    }
+    let (listing, has_markup) = render_lines(&lines, true);
+    let subs = if has_markup {
+        ",subs=\"+quotes,+macros\""
+    } else {
+        ""
+    };
+    format!("\n\n[source,{language}{subs}]\n----\n{listing}\n----\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    fn render(content: &str) -> String {
+        let ctx = FileContext::from_str("class A {}").unwrap();
+        render_snippet(content, None, &ctx)
+    }
+
+    #[test]
+    fn renders_inline_snippets() {
+        let content = " :\n    if (v.isPresent()) {\n        System.out.println(v.get());\n    }\n";
+        assert_eq!(
+            render(content),
+            "\n\n[source,java]\n----\nif (v.isPresent()) {\n    System.out.println(v.get());\n}\n----\n\n"
+        );
+    }
+
+    #[test]
+    fn applies_markup() {
+        let content = r#" lang=java :
+var name = "World"; // @replace substring="World" replacement="..."
+System.out.println(name); // @highlight substring="println" type=italic
+// @highlight region regex="\d+" :
+int x = 42;
+int y = 7;
+// @end
+"#;
+        assert_eq!(
+            render(content),
+            "\n\n[source,java,subs=\"+quotes,+macros\"]\n----\npass:c[var name = \"...\";]\n\
+             pass:c[System.out.]__pass:c[println]__pass:c[(name);]\n\
+             pass:c[int x = ]**pass:c[42]**pass:c[;]\npass:c[int y = ]**pass:c[7]**pass:c[;]\n----\n\n"
+        );
+    }
+
+    #[test]
+    fn escapes_code_around_markup() {
+        let content = r##" :
+int area = a * b * c; // @highlight substring="area"
+String _field_ = "#x# pass:[y]";
+"##;
+        assert_eq!(
+            render(content),
+            "\n\n[source,java,subs=\"+quotes,+macros\"]\n----\npass:c[int ]**pass:c[area]**pass:c[ = a * b * c;]\n\
+             pass:c[String _field_ = \"#x# pass:[y\\]\";]\n----\n\n"
+        );
+    }
+
+    #[test]
+    fn renders_fenced_code_blocks() {
+        let ctx = FileContext::from_str("class A {}")
+            .unwrap()
+            .with_text_format(TextFormat::Markdown);
+        let content = " :\nint x = 42; // @highlight substring=\"42\"\n";
+        assert_eq!(
+            render_snippet(content, None, &ctx),
+            "\n\n```java\nint x = 42;\n```\n\n"
+        );
+    }
+
+    #[test]
+    fn selects_regions() {
+        let content = r#" region=main :
+class Example {
+    // @start region=main
+    void run() {}
+    // @end region=main
+}
+"#;
+        assert_eq!(
+            render(content),
+            "\n\n[source,java]\n----\nvoid run() {}\n----\n\n"
+        );
+    }
+
+    #[test]
+    fn reads_external_files() {
+        let dir = std::env::temp_dir().join("javadoc2adoc-snippet-test");
+        fs::create_dir_all(dir.join("pkg")).unwrap();
+        fs::write(
+            dir.join("pkg/Example.java"),
+            "// @start region=r\nrun();\n// @end\n",
+        )
+        .unwrap();
+        fs::write(dir.join("app.properties"), "key=value\n").unwrap();
+        let ctx = FileContext::from_str("class A {}")
+            .unwrap()
+            .with_snippet_dirs(vec![PathBuf::from("missing"), dir]);
+        assert_eq!(
+            render_snippet("class=pkg.Example region=r", None, &ctx),
+            "\n\n[source,java]\n----\nrun();\n----\n\n"
+        );
+        assert_eq!(
+            render_snippet("file=app.properties", None, &ctx),
+            "\n\n[source,properties]\n----\nkey=value\n----\n\n"
+        );
+        assert_eq!(render_snippet("file=Missing.java", None, &ctx), "");
+    }
+
+    #[test]
+    fn protects_snippets() {
+        let text = "Example: {@snippet :\n  <b>{code}</b>\n} done";
+        let (protected, snippets) = protect_snippets(text);
+        assert_eq!(protected, "Example: {@snippet-0} done");
+        assert_eq!(restore_snippets(&protected, &snippets), text);
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use tree_sitter::Node;
+
+use super::{
+    anchor::node_anchor, inheritance::TypeHierarchy, local_type_name, FileContext,
+    JavaDocableElement, TYPE_DECLARATIONS,
+};
+
+/// All documented types of a run, used to link between the generated pages, together with the
+/// hierarchy of all types to inherit documentation
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    types: HashMap<String, TypeSymbol>,
+    hierarchy: HashMap<String, TypeHierarchy>,
+}
+
+impl SymbolTable {
+    pub fn insert(&mut self, symbol: TypeSymbol) {
+        self.types.insert(symbol.fqn.clone(), symbol);
+    }
+
+    pub fn get(&self, fqn: &str) -> Option<&TypeSymbol> {
+        self.types.get(fqn)
+    }
+
+    pub fn insert_hierarchy(&mut self, fqn: String, hierarchy: TypeHierarchy) {
+        self.hierarchy.insert(fqn, hierarchy);
+    }
+
+    /// Supertypes and method documentation of a type, documented or not
+    pub fn hierarchy(&self, fqn: &str) -> Option<&TypeHierarchy> {
+        self.hierarchy.get(fqn)
+    }
+
+    /// Adds everything from the symbols of another file
+    pub fn merge(&mut self, other: SymbolTable) {
+        self.types.extend(other.types);
+        self.hierarchy.extend(other.hierarchy);
+    }
+}
+
+/// A documented type and the page it is rendered to
+#[derive(Debug, Clone)]
+pub struct TypeSymbol {
+    pub fqn: String,
+    pub page: PathBuf,
+    pub members: Vec<MemberSymbol>,
+}
+
+/// A documented field, method, constructor, enum constant or annotation element
+#[derive(Debug, Clone)]
+pub struct MemberSymbol {
+    pub name: String,
+    pub anchor: String,
+    /// Initializer of fields, for `{@value}`
+    pub value: Option<String>,
+}
+
+impl TypeSymbol {
+    /// Finds a member by its anchor, falling back to the first member of that name like javadoc
+    /// does for references without parameter list
+    pub fn find_member(&self, name: &str, anchor: Option<&str>) -> Option<&MemberSymbol> {
+        anchor
+            .and_then(|anchor| self.members.iter().find(|member| member.anchor == anchor))
+            .or_else(|| self.members.iter().find(|member| member.name == name))
+    }
+}
+
+/// Collects the documented types of a file, including nested types
+pub fn collect_symbols(
+    elements: &[JavaDocableElement<'_>],
+    ctx: &FileContext,
+    page: &Path,
+) -> Vec<TypeSymbol> {
+    let mut symbols: Vec<TypeSymbol> = vec![];
+    for element in elements {
+        let node = element.get_node();
+        if !TYPE_DECLARATIONS.contains(&node.grammar_name()) {
+            continue;
+        }
+        let Some(local_type) = local_type_name(node, ctx) else {
+            continue;
+        };
+        let members = element
+            .get_children()
+            .iter()
+            .map(|child| child.get_node())
+            .filter(|child| !TYPE_DECLARATIONS.contains(&child.grammar_name()))
+            .filter_map(|child| {
+                Some(MemberSymbol {
+                    name: member_name(child, ctx)?,
+                    anchor: node_anchor(child, ctx)?,
+                    value: field_value(child, ctx),
+                })
+            })
+            .collect();
+        symbols.push(TypeSymbol {
+            fqn: ctx.fqn(&local_type),
+            page: page.to_owned(),
+            members,
+        });
+        symbols.extend(collect_symbols(element.get_children(), ctx, page));
+    }
+    symbols
+}
+
+fn member_name(node: Node<'_>, ctx: &FileContext) -> Option<String> {
+    let node = match node.grammar_name() {
+        "field_declaration" | "constant_declaration" => node.child_by_field_name("declarator")?,
+        _ => node,
+    };
+    let name = node.child_by_field_name("name")?;
+    Some(ctx.source_for_range(&name.range()).to_owned())
+}
+
+/// Source of the initializer of a field, like `"abc"` for `String FOO = "abc";`
+pub fn field_value(node: Node<'_>, ctx: &FileContext) -> Option<String> {
+    if !matches!(
+        node.grammar_name(),
+        "field_declaration" | "constant_declaration"
+    ) {
+        return None;
+    }
+    let declarator = node.child_by_field_name("declarator")?;
+    let value = declarator.child_by_field_name("value")?;
+    Some(ctx.source_for_range(&value.range()).to_owned())
+}
@@ -0,0 +1,394 @@
+use tracing::trace;
+
+/// A single javadoc block tag like `@param` or `@return`, with continuation lines already joined
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockTag {
+    Param {
+        name: String,
+        description: String,
+    },
+    Return(String),
+    /// `@throws` as well as its older synonym `@exception`
+    Throws {
+        exception: String,
+        description: String,
+    },
+    See(String),
+    Since(String),
+    Deprecated(String),
+    Author(String),
+    Version(String),
+    Serial(String),
+    SerialData(String),
+    SerialField {
+        name: String,
+        fieldtype: String,
+        description: String,
+    },
+    Unknown {
+        tag: String,
+        content: String,
+    },
+}
+
+impl BlockTag {
+    fn parse(tag: &str, content: &str) -> Self {
+        let content = content.trim();
+        match tag {
+            "param" => {
+                let (name, description) = split_first_word(content);
+                Self::Param { name, description }
+            }
+            "return" => Self::Return(content.to_owned()),
+            "throws" | "exception" => {
+                let (exception, description) = split_first_word(content);
+                Self::Throws {
+                    exception,
+                    description,
+                }
+            }
+            "see" => Self::See(content.to_owned()),
+            "since" => Self::Since(content.to_owned()),
+            "deprecated" => Self::Deprecated(content.to_owned()),
+            "author" => Self::Author(content.to_owned()),
+            "version" => Self::Version(content.to_owned()),
+            "serial" => Self::Serial(content.to_owned()),
+            "serialData" => Self::SerialData(content.to_owned()),
+            "serialField" => {
+                let (name, rest) = split_first_word(content);
+                let (fieldtype, description) = split_first_word(&rest);
+                Self::SerialField {
+                    name,
+                    fieldtype,
+                    description,
+                }
+            }
+            _ => Self::Unknown {
+                tag: tag.to_owned(),
+                content: content.to_owned(),
+            },
+        }
+    }
+
+    /// Name of the tag as written in the source, without the leading `@`
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Param { .. } => "param",
+            Self::Return(_) => "return",
+            Self::Throws { .. } => "throws",
+            Self::See(_) => "see",
+            Self::Since(_) => "since",
+            Self::Deprecated(_) => "deprecated",
+            Self::Author(_) => "author",
+            Self::Version(_) => "version",
+            Self::Serial(_) => "serial",
+            Self::SerialData(_) => "serialData",
+            Self::SerialField { .. } => "serialField",
+            Self::Unknown { tag, .. } => tag,
+        }
+    }
+
+    /// Content of the tag, with any leading names re-joined to the description
+    pub fn content(&self) -> String {
+        match self {
+            Self::Param { name, description } => join_words(&[name, description]),
+            Self::Throws {
+                exception,
+                description,
+            } => join_words(&[exception, description]),
+            Self::SerialField {
+                name,
+                fieldtype,
+                description,
+            } => join_words(&[name, fieldtype, description]),
+            Self::Return(content)
+            | Self::See(content)
+            | Self::Since(content)
+            | Self::Deprecated(content)
+            | Self::Author(content)
+            | Self::Version(content)
+            | Self::Serial(content)
+            | Self::SerialData(content)
+            | Self::Unknown { content, .. } => content.clone(),
+        }
+    }
+}
+
+/// Structured content of a javadoc comment
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Javadoc {
+    /// Everything in front of the first block tag
+    pub description: String,
+    pub tags: Vec<BlockTag>,
+}
+
+impl Javadoc {
+    /// Parses the already stripped lines of a javadoc comment
+    pub fn parse<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut description: Vec<String> = vec![];
+        let mut tags: Vec<BlockTag> = vec![];
+        // tag name and lines of the block tag currently being collected
+        let mut current: Option<(String, Vec<String>)> = None;
+        // inline tags like {@code ...} may span lines, an @ inside them never starts a block tag
+        let mut inline_depth: usize = 0;
+        // the same goes for fenced code blocks of markdown comments
+        let mut fenced = false;
+
+        for line in lines {
+            let line = line.as_ref();
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fenced = !fenced;
+            }
+            let starts_tag = inline_depth == 0
+                && !fenced
+                && trimmed.starts_with('@')
+                && trimmed[1..].starts_with(|c: char| c.is_ascii_alphabetic());
+            if starts_tag {
+                if let Some((tag, content)) = current.take() {
+                    tags.push(BlockTag::parse(&tag, &content.join("\n")));
+                }
+                let line = &trimmed[1..];
+                let (tag, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                trace!("Found block tag {tag:?}");
+                current = Some((tag.to_owned(), vec![rest.to_owned()]));
+            } else if let Some((_, content)) = current.as_mut() {
+                content.push(trimmed.to_owned());
+            } else {
+                description.push(line.to_owned());
+            }
+            inline_depth = track_inline_depth(line, inline_depth);
+        }
+        if let Some((tag, content)) = current.take() {
+            tags.push(BlockTag::parse(&tag, &content.join("\n")));
+        }
+
+        let description = description.join("\n").trim().to_owned();
+        Self { description, tags }
+    }
+
+    /// Applies `f` to the description and the free text of every block tag, leaving names like
+    /// those of parameters or exceptions untouched
+    pub fn map_text(&self, f: impl Fn(&str) -> String) -> Self {
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| match tag {
+                BlockTag::Param { name, description } => BlockTag::Param {
+                    name: name.clone(),
+                    description: f(description),
+                },
+                BlockTag::Return(content) => BlockTag::Return(f(content)),
+                BlockTag::Throws {
+                    exception,
+                    description,
+                } => BlockTag::Throws {
+                    exception: exception.clone(),
+                    description: f(description),
+                },
+                BlockTag::See(content) => BlockTag::See(f(content)),
+                BlockTag::Since(content) => BlockTag::Since(f(content)),
+                BlockTag::Deprecated(content) => BlockTag::Deprecated(f(content)),
+                BlockTag::Author(content) => BlockTag::Author(f(content)),
+                BlockTag::Version(content) => BlockTag::Version(f(content)),
+                BlockTag::Serial(content) => BlockTag::Serial(f(content)),
+                BlockTag::SerialData(content) => BlockTag::SerialData(f(content)),
+                BlockTag::SerialField {
+                    name,
+                    fieldtype,
+                    description,
+                } => BlockTag::SerialField {
+                    name: name.clone(),
+                    fieldtype: fieldtype.clone(),
+                    description: f(description),
+                },
+                BlockTag::Unknown { tag, content } => BlockTag::Unknown {
+                    tag: tag.clone(),
+                    content: f(content),
+                },
+            })
+            .collect();
+        Self {
+            description: f(&self.description),
+            tags,
+        }
+    }
+
+    /// Description of the parameter `name`, if documented by a `@param` tag
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find_map(|tag| match tag {
+            BlockTag::Param {
+                name: param,
+                description,
+            } if param == name => Some(description.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Content of the `@return` tag, if present
+    pub fn returns(&self) -> Option<&str> {
+        self.tags.iter().find_map(|tag| match tag {
+            BlockTag::Return(content) => Some(content.as_str()),
+            _ => None,
+        })
+    }
+
+    /// All `@throws` and `@exception` tags as pairs of exception and description
+    pub fn throws(&self) -> Vec<(&str, &str)> {
+        self.tags
+            .iter()
+            .filter_map(|tag| match tag {
+                BlockTag::Throws {
+                    exception,
+                    description,
+                } => Some((exception.as_str(), description.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn split_first_word(content: &str) -> (String, String) {
+    let content = content.trim_start();
+    let (word, rest) = content
+        .split_once(char::is_whitespace)
+        .unwrap_or((content, ""));
+    (word.to_owned(), rest.trim().to_owned())
+}
+
+fn join_words(words: &[&String]) -> String {
+    let words: Vec<&str> = words
+        .iter()
+        .map(|word| word.as_str())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.join(" ")
+}
+
+fn track_inline_depth(line: &str, depth: usize) -> usize {
+    let mut depth = depth;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if depth > 0 || chars.peek() == Some(&'@') => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            _ => (),
+        }
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINES: &[&str] = &[
+        "Returns an Image object.",
+        "",
+        "Some more text.",
+        "@param  url  an absolute URL giving",
+        "             the base location",
+        "@param name the location",
+        "@return      the image at the specified URL",
+        "@throws IOException if reading fails",
+        "@exception IllegalStateException when in a bad mood",
+        "@see         Image",
+        "@since 1.0",
+        "@serialField count int the count",
+        "@apiNote do not use",
+    ];
+
+    #[test]
+    fn parses_description() {
+        let javadoc = Javadoc::parse(LINES);
+        assert_eq!(
+            javadoc.description,
+            "Returns an Image object.\n\nSome more text."
+        );
+    }
+
+    #[test]
+    fn parses_tags() {
+        let javadoc = Javadoc::parse(LINES);
+        assert_eq!(
+            javadoc.tags,
+            vec![
+                BlockTag::Param {
+                    name: "url".to_owned(),
+                    description: "an absolute URL giving\nthe base location".to_owned()
+                },
+                BlockTag::Param {
+                    name: "name".to_owned(),
+                    description: "the location".to_owned()
+                },
+                BlockTag::Return("the image at the specified URL".to_owned()),
+                BlockTag::Throws {
+                    exception: "IOException".to_owned(),
+                    description: "if reading fails".to_owned()
+                },
+                BlockTag::Throws {
+                    exception: "IllegalStateException".to_owned(),
+                    description: "when in a bad mood".to_owned()
+                },
+                BlockTag::See("Image".to_owned()),
+                BlockTag::Since("1.0".to_owned()),
+                BlockTag::SerialField {
+                    name: "count".to_owned(),
+                    fieldtype: "int".to_owned(),
+                    description: "the count".to_owned()
+                },
+                BlockTag::Unknown {
+                    tag: "apiNote".to_owned(),
+                    content: "do not use".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn lookups() {
+        let javadoc = Javadoc::parse(LINES);
+        assert_eq!(
+            javadoc.param("url"),
+            Some("an absolute URL giving\nthe base location")
+        );
+        assert_eq!(javadoc.param("missing"), None);
+        assert_eq!(javadoc.returns(), Some("the image at the specified URL"));
+        assert_eq!(
+            javadoc.throws(),
+            vec![
+                ("IOException", "if reading fails"),
+                ("IllegalStateException", "when in a bad mood")
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_annotations_in_inline_tags() {
+        let lines = [
+            "Use it like this: {@code",
+            "@Override",
+            "public void run() {}",
+            "}",
+            "@since 2.0",
+        ];
+        let javadoc = Javadoc::parse(lines);
+        assert_eq!(
+            javadoc.description,
+            "Use it like this: {@code\n@Override\npublic void run() {}\n}"
+        );
+        assert_eq!(javadoc.tags, vec![BlockTag::Since("2.0".to_owned())]);
+    }
+
+    #[test]
+    fn ignores_annotations_in_code_fences() {
+        let lines = ["Example:", "```", "@Override", "```", "@since 2.0"];
+        let javadoc = Javadoc::parse(lines);
+        assert_eq!(javadoc.description, "Example:\n```\n@Override\n```");
+        assert_eq!(javadoc.tags, vec![BlockTag::Since("2.0".to_owned())]);
+    }
+}
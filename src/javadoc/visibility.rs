@@ -0,0 +1,175 @@
+//! Access levels of declarations, to only document the API visible from a given level like the
+//! `-public`, `-protected`, `-package` and `-private` options of the javadoc tool.
+
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use tree_sitter::Node;
+
+use super::FileContext;
+
+/// Access level of a declaration, ordered from least to most visible
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    #[default]
+    Private,
+    Package,
+    Protected,
+    Public,
+}
+
+impl Display for Visibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Visibility::Private => write!(f, "private"),
+            Visibility::Package => write!(f, "package"),
+            Visibility::Protected => write!(f, "protected"),
+            Visibility::Public => write!(f, "public"),
+        }
+    }
+}
+
+/// Access level of a declaration, from its modifiers or the implicit rules of the java language
+/// specification where there are none
+pub fn access(node: Node<'_>, ctx: &FileContext) -> Visibility {
+    match node.grammar_name() {
+        // packages and modules have no access level, annotation elements and enum constants are
+        // always public
+        "package_declaration"
+        | "module_declaration"
+        | "annotation_type_element_declaration"
+        | "enum_constant" => return Visibility::Public,
+        _ => (),
+    }
+    if let Some(explicit) = explicit_access(node, ctx) {
+        return explicit;
+    }
+    match node.parent().map(|parent| parent.grammar_name()) {
+        // members of interfaces and annotation types are implicitly public
+        Some("interface_body" | "annotation_type_body") => Visibility::Public,
+        // enum constructors are implicitly private
+        Some("enum_body_declarations") if node.grammar_name() == "constructor_declaration" => {
+            Visibility::Private
+        }
+        _ => Visibility::Package,
+    }
+}
+
+/// Access level given by the modifiers of a declaration, if any
+fn explicit_access(node: Node<'_>, ctx: &FileContext) -> Option<Visibility> {
+    let mut cursor = node.walk();
+    let modifiers = node
+        .children(&mut cursor)
+        .find(|child| child.grammar_name() == "modifiers")?;
+    let mut cursor = modifiers.walk();
+    let explicit = modifiers.children(&mut cursor).find_map(|modifier| {
+        match ctx.source_for_range(&modifier.range()) {
+            "public" => Some(Visibility::Public),
+            "protected" => Some(Visibility::Protected),
+            "private" => Some(Visibility::Private),
+            _ => None,
+        }
+    });
+    explicit
+}
+
+/// Whether a declaration is visible enough to be documented
+pub fn is_visible(node: Node<'_>, ctx: &FileContext) -> bool {
+    access(node, ctx) >= ctx.selection().visibility
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        javadoc::{node_to_docable, JavaDocableElement, Selection},
+        parser::parse_string,
+    };
+
+    use super::*;
+
+    const INPUT: &str = "
+public class Outer {
+    public int a;
+    protected int b;
+    int c;
+    private int d;
+    interface Inner {
+        void run();
+        private void helper() {}
+    }
+    enum Kind {
+        A;
+        Kind() {}
+    }
+}";
+
+    /// Access levels of all declarations, in order of appearance
+    fn collect(node: Node<'_>, ctx: &FileContext, found: &mut Vec<Visibility>) {
+        if node.grammar_name().ends_with("_declaration") || node.grammar_name() == "enum_constant" {
+            found.push(access(node, ctx));
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect(child, ctx, found);
+        }
+    }
+
+    #[test]
+    fn finds_access_levels() {
+        let tree = parse_string(INPUT).unwrap();
+        let ctx = FileContext::from_str(INPUT).unwrap();
+        let mut found = vec![];
+        collect(tree.root_node(), &ctx, &mut found);
+        assert_eq!(
+            found,
+            vec![
+                Visibility::Public,
+                Visibility::Public,
+                Visibility::Protected,
+                Visibility::Package,
+                Visibility::Private,
+                Visibility::Package,
+                Visibility::Public,
+                Visibility::Private,
+                Visibility::Package,
+                Visibility::Public,
+                Visibility::Private,
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_by_visibility() {
+        let sourcecode = "
+/** Outer */
+public class Outer {
+    /** public */
+    public int a;
+    /** protected */
+    protected int b;
+    /** package */
+    int c;
+    /** private */
+    private int d;
+}";
+        let tree = parse_string(sourcecode).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let ctx = FileContext::from_str(sourcecode)
+            .unwrap()
+            .with_selection(Selection {
+                visibility: Visibility::Protected,
+                ..Default::default()
+            });
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &ctx))
+            .collect();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].get_children().len(), 2);
+    }
+}
@@ -0,0 +1,140 @@
+//! Export of the documentation model as JSON, following the versioned schema described in
+//! `docs/json.md`.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::javadoc::model::Page;
+
+/// Version of the JSON schema, increased with every incompatible change
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The documented elements of a single source file
+#[derive(Debug, Serialize)]
+pub struct SourceFile {
+    /// Path of the source file, as found by the input pattern
+    pub source: String,
+    #[serde(flatten)]
+    pub page: Page,
+}
+
+impl SourceFile {
+    pub fn new(source: &Path, page: Page) -> Self {
+        Self {
+            source: source.to_string_lossy().into_owned(),
+            page,
+        }
+    }
+}
+
+/// The JSON document written for a single source file
+#[derive(Serialize)]
+struct FileDocument<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    file: &'a SourceFile,
+}
+
+/// The JSON document combining all source files
+#[derive(Serialize)]
+struct Document<'a> {
+    schema_version: u32,
+    files: Vec<&'a SourceFile>,
+}
+
+/// Renders the JSON document of a single source file
+pub fn render_file(file: &SourceFile) -> anyhow::Result<String> {
+    let document = FileDocument {
+        schema_version: SCHEMA_VERSION,
+        file,
+    };
+    Ok(format!("{}\n", serde_json::to_string_pretty(&document)?))
+}
+
+/// Renders a single JSON document of all source files, ordered by their path
+pub fn render_document(files: &[&SourceFile]) -> anyhow::Result<String> {
+    let mut files = files.to_vec();
+    files.sort_by(|a, b| a.source.cmp(&b.source));
+    let document = Document {
+        schema_version: SCHEMA_VERSION,
+        files,
+    };
+    Ok(format!("{}\n", serde_json::to_string_pretty(&document)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use serde_json::Value;
+
+    use crate::{
+        javadoc::{model::page, node_to_docable, FileContext, JavaDocableElement, TextFormat},
+        parser::parse_string,
+    };
+
+    use super::*;
+
+    const INPUT: &str = "
+package com.acme;
+
+/** A box. */
+public class Box<T> implements Closeable {
+    /**
+     * Opens the box.
+     * @param force whether to force it
+     * @throws IOException when stuck
+     */
+    public void open(boolean force) throws IOException {}
+}";
+
+    fn source_file(path: &str) -> SourceFile {
+        let tree = parse_string(INPUT).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let ctx = FileContext::from_str(INPUT)
+            .unwrap()
+            .with_text_format(TextFormat::Markdown);
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &ctx))
+            .collect();
+        SourceFile::new(Path::new(path), page(&children, &ctx))
+    }
+
+    #[test]
+    fn renders_files() {
+        let file = source_file("src/com/acme/Box.java");
+        let json: Value = serde_json::from_str(&render_file(&file).unwrap()).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["source"], "src/com/acme/Box.java");
+        assert_eq!(json["package"], "com.acme");
+
+        let class = &json["elements"][0];
+        assert_eq!(class["kind"], "class");
+        assert_eq!(class["qualified_name"], "com.acme.Box");
+        assert_eq!(class["type_parameters"][0], "T");
+        assert_eq!(class["implements"][0], "Closeable");
+        assert_eq!(class["location"]["start_line"], 5);
+
+        let method = &class["children"][0];
+        assert_eq!(method["kind"], "method");
+        assert_eq!(method["qualified_name"], "com.acme.Box#open(boolean)");
+        assert_eq!(method["modifiers"][0], "public");
+        assert_eq!(method["parameters"][0]["type"], "boolean");
+        assert_eq!(method["throws"][0]["type"], "IOException");
+        assert_eq!(method["tags"][0]["name"], "param");
+        assert_eq!(method["tags"][0]["argument"], "force");
+    }
+
+    #[test]
+    fn renders_documents() {
+        let b = source_file("src/b/Box.java");
+        let a = source_file("src/a/Box.java");
+        let json: Value = serde_json::from_str(&render_document(&[&b, &a]).unwrap()).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["files"][0]["source"], "src/a/Box.java");
+        assert_eq!(json["files"][1]["source"], "src/b/Box.java");
+    }
+}
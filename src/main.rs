@@ -1,32 +1,90 @@
+use anyhow::{anyhow, bail};
 use clap::Parser;
 use config::Config;
-use output::build_output_path;
-use rust_i18n::{i18n, set_locale};
-use std::path::PathBuf;
+use output::{Format, Layout, Output};
+use rust_i18n::{i18n, set_locale, t};
+use std::{
+    mem::take,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use classdoc::from_sourcecode;
+use classdoc::{
+    declared_package, from_sourcecode, render_package_types, render_single_document,
+    symbols_from_sourcecode, ClassDoc, RenderSettings,
+};
 use futures::future::join_all;
 use glob::glob;
+use index::{render_index, render_nav, PackageIndex};
+use javadoc::{symbols::SymbolTable, Selection};
+use json::{render_document, SourceFile};
 use macro_rules_attribute::apply;
 use smol::{
     fs::{read_to_string, write, DirBuilder},
     Executor, Task,
 };
 use smol_macros::main;
-use tracing::{debug, info, trace};
+use template::Templates;
+use tracing::{debug, info, trace, warn};
 
 mod classdoc;
 mod config;
+mod index;
 mod javadoc;
+mod json;
 mod output;
 mod parser;
+mod template;
 
 i18n!();
 
-async fn doc_from_file(path: &PathBuf) -> anyhow::Result<String> {
-    let content = read_to_string(path).await?;
-    let rendered = from_sourcecode(&content)?;
-    Ok(rendered)
+const PACKAGE_INFO: &str = "package-info.java";
+/// Name of the package overview pages, without extension
+const PACKAGE_INDEX: &str = "index";
+/// Overview of all packages and types, in the output directory, without extension
+const INDEX: &str = "index";
+/// Antora style navigation file, in the output directory
+const NAV: &str = "nav.adoc";
+/// Antora component descriptor, in the output directory
+const ANTORA_YML: &str = "antora.yml";
+/// Directory next to a source file holding its external snippets, like for the javadoc tool
+const SNIPPET_FILES: &str = "snippet-files";
+
+/// A java file read in the first pass, with the types documented in it
+struct Source {
+    path: PathBuf,
+    outpath: PathBuf,
+    content: String,
+    symbols: SymbolTable,
+}
+
+/// Path of the page generated for the java file `entry`, declared in `package`
+fn output_file(
+    entry: &Path,
+    package: Option<&str>,
+    output: &Output,
+    format: Format,
+) -> anyhow::Result<PathBuf> {
+    let outdir = output.page_dir(entry, package)?;
+    let filename = entry
+        .file_name()
+        .ok_or_else(|| anyhow!("Failed to get file name"))?;
+    let extension = format.extension();
+    let filename = if filename == PACKAGE_INFO {
+        format!("{PACKAGE_INDEX}.{extension}")
+    } else {
+        filename
+            .to_string_lossy()
+            .replace(".java", &format!(".{extension}"))
+    };
+    Ok(outdir.join(filename))
+}
+
+/// Whether `outpath` is the overview page of a package
+fn is_package_index(outpath: &Path) -> bool {
+    outpath
+        .file_stem()
+        .is_some_and(|name| name == PACKAGE_INDEX)
 }
 
 #[apply(main!)]
@@ -37,13 +95,55 @@ async fn main(ex: &Executor<'_>) -> anyhow::Result<()> {
 
     let glob_in = cfg.input;
     debug!("glob pattern: {glob_in:?}");
-    let outdir = cfg.output;
-    debug!("out dir: {outdir}");
+    let output = Output {
+        layout: cfg.layout,
+        outdir: PathBuf::from(cfg.output),
+        module: cfg.antora_module,
+        source_roots: cfg.source_root.iter().map(PathBuf::from).collect(),
+    };
+    debug!("output: {output:?}");
+    let format = cfg.format;
+    debug!("format: {format}");
+    if format != Format::Asciidoc {
+        if output.layout == Layout::Antora {
+            bail!("The antora layout requires the asciidoc format");
+        }
+        if cfg.tagged_regions {
+            bail!("Tagged regions require the asciidoc format");
+        }
+    }
+    if format == Format::Json && cfg.templates.is_some() {
+        bail!("Templates can't be used with the json format");
+    }
     let locale = cfg.locale;
     debug!("locale: {locale}");
     set_locale(&locale.to_string());
+    let snippet_path: Vec<PathBuf> = cfg.snippet_path.iter().map(PathBuf::from).collect();
+    debug!("snippet path: {snippet_path:?}");
+    let selection = Selection {
+        include_undocumented: cfg.include_undocumented,
+        visibility: cfg.visibility,
+    };
+    debug!("selection: {selection:?}");
+    let single_file = cfg.single_file.map(|file| output.outdir.join(file));
+    debug!("single file: {single_file:?}");
+    // the single document nests types below their packages
+    let level = if single_file.is_some() { 2 } else { 0 };
+    let tagged_regions = cfg.tagged_regions;
+    let templates = match (cfg.templates, format) {
+        (Some(dir), _) => {
+            let templates = Templates::load(format.text_format(), Some(Path::new(&dir))).await?;
+            Some(Arc::new(templates))
+        }
+        (None, Format::Markdown) => {
+            Some(Arc::new(Templates::load(format.text_format(), None).await?))
+        }
+        (None, Format::Asciidoc | Format::Json) => None,
+    };
+    debug!("templates: {templates:?}");
 
-    let mut tasks: Vec<Task<()>> = vec![];
+    // first pass: find all documented types, so the pages can link to each other
+    let mut tasks: Vec<Task<Source>> = vec![];
     for entry in glob(&glob_in)? {
         let entry = entry?;
         info!("Trying to handle file {entry:?}");
@@ -53,26 +153,30 @@ async fn main(ex: &Executor<'_>) -> anyhow::Result<()> {
                 trace!("Has an extension");
                 if extension == "java" {
                     debug!("Found java file at {entry:?}");
-                    let outdir = outdir.clone(); // clone to work around move
+                    let output = output.clone();
+                    let single_file = single_file.clone();
                     let task = ex.spawn(async move {
-                        let classdoc = doc_from_file(&entry).await.unwrap();
-                        trace!("Got {classdoc:?}");
-                        let outdir = build_output_path(&entry, &outdir).unwrap();
-                        let filename = entry.file_name().unwrap();
-                        let filename = filename.to_string_lossy().replace(".java", ".adoc");
-                        trace!("Built outdir {outdir:?}");
-                        DirBuilder::new()
-                            .recursive(true)
-                            .create(outdir.clone())
-                            .await
-                            .unwrap();
-                        trace!("Outdir {outdir:?} created");
-                        let outpath = outdir.join(filename);
-                        if classdoc.is_empty() {
-                            info!("Skipping write to {outpath:?} as output file would be empty");
-                        } else {
-                            debug!("Writing to {outpath:?}");
-                            write(outpath, classdoc).await.unwrap()
+                        let content = read_to_string(&entry).await.unwrap();
+                        let package = declared_package(&content).unwrap();
+                        let outpath = match single_file {
+                            Some(single_file) => single_file,
+                            None => {
+                                output_file(&entry, package.as_deref(), &output, format).unwrap()
+                            }
+                        };
+                        let symbols = symbols_from_sourcecode(
+                            &content,
+                            &outpath,
+                            selection,
+                            format.text_format(),
+                        )
+                        .unwrap();
+                        trace!("Found symbols {symbols:?}");
+                        Source {
+                            path: entry,
+                            outpath,
+                            content,
+                            symbols,
                         }
                     });
                     tasks.push(task);
@@ -80,7 +184,201 @@ async fn main(ex: &Executor<'_>) -> anyhow::Result<()> {
             }
         }
     }
-    join_all(tasks).await;
+    let mut sources = join_all(tasks).await;
+
+    let mut symbols = SymbolTable::default();
+    for source in sources.iter_mut() {
+        symbols.merge(take(&mut source.symbols));
+    }
+    let symbols = Arc::new(symbols);
+
+    // second pass: render and write all pages
+    let mut tasks: Vec<Task<(PathBuf, ClassDoc)>> = vec![];
+    for source in sources {
+        let Source {
+            path,
+            outpath,
+            content,
+            ..
+        } = source;
+        let symbols = symbols.clone();
+        let xref_root = output.xref_root();
+        let snippet_dirs: Vec<PathBuf> = path
+            .parent()
+            .map(|dir| dir.join(SNIPPET_FILES))
+            .into_iter()
+            .chain(snippet_path.iter().cloned())
+            .collect();
+        let is_single_file = single_file.is_some();
+        let templates = templates.clone();
+        let task = ex.spawn(async move {
+            let settings = RenderSettings {
+                source: path.clone(),
+                xref_root,
+                snippet_dirs,
+                selection,
+                level,
+                tagged_regions,
+                format,
+                templates,
+            };
+            let classdoc = from_sourcecode(&content, symbols, &outpath, settings).unwrap();
+            trace!("Got {classdoc:?}");
+            if is_single_file {
+                debug!("Collecting {path:?} for the single output file");
+                return (outpath, classdoc);
+            }
+            let outdir = outpath.parent().unwrap();
+            DirBuilder::new()
+                .recursive(true)
+                .create(outdir)
+                .await
+                .unwrap();
+            trace!("Outdir {outdir:?} created");
+            if classdoc.content.is_empty() {
+                info!("Skipping write to {outpath:?} as output file would be empty");
+            } else if is_package_index(&outpath) && format != Format::Json {
+                debug!("Deferring write to {outpath:?} until all types are known");
+            } else {
+                debug!("Writing to {outpath:?}");
+                write(&outpath, &classdoc.content).await.unwrap()
+            }
+            (outpath, classdoc)
+        });
+        tasks.push(task);
+    }
+    let docs = join_all(tasks).await;
+
+    if let Some(single_file) = single_file {
+        let docs: Vec<&ClassDoc> = docs.iter().map(|(_, classdoc)| classdoc).collect();
+        if let Some(outdir) = single_file.parent() {
+            DirBuilder::new().recursive(true).create(outdir).await?;
+        }
+        debug!("Writing all documentation to {single_file:?}");
+        let content = match format {
+            Format::Json => {
+                let files: Vec<&SourceFile> = docs
+                    .iter()
+                    .filter_map(|classdoc| classdoc.json.as_ref())
+                    .collect();
+                render_document(&files)?
+            }
+            Format::Asciidoc | Format::Markdown => {
+                render_single_document(&docs, format.text_format())
+            }
+        };
+        write(&single_file, content).await?;
+        return Ok(());
+    }
+    if format == Format::Json {
+        debug!("Skipping overview pages, as the JSON files hold all documented elements");
+        return Ok(());
+    }
+
+    write_package_indexes(&docs, &output, format).await?;
+    write_index(&docs, &output, format).await?;
+    if output.layout == Layout::Antora {
+        write_antora_descriptor(&output).await?;
+    }
+
+    Ok(())
+}
+
+/// Writes the overview pages of all documented packages, listing the documented types of each package
+async fn write_package_indexes(
+    docs: &[(PathBuf, ClassDoc)],
+    output: &Output,
+    format: Format,
+) -> anyhow::Result<()> {
+    let packages = docs
+        .iter()
+        .filter(|(outpath, classdoc)| is_package_index(outpath) && !classdoc.content.is_empty());
+    for (outpath, package) in packages {
+        let mut types: Vec<(String, String)> = docs
+            .iter()
+            .filter(|(other, classdoc)| {
+                other != outpath
+                    && classdoc.package == package.package
+                    && !classdoc.content.is_empty()
+            })
+            .flat_map(|(other, classdoc)| {
+                let path = output.xref(outpath, other);
+                classdoc
+                    .types
+                    .iter()
+                    .map(move |name| (name.clone(), path.clone()))
+            })
+            .collect();
+        types.sort();
+
+        let types = render_package_types(&types, format.text_format());
+        let content = format!("{}{types}", package.content);
+        debug!("Writing package overview to {outpath:?}");
+        write(outpath, content).await?;
+    }
+    Ok(())
+}
+
+/// Writes the overview of all documented packages and types and, for asciidoc, the navigation file
+async fn write_index(
+    docs: &[(PathBuf, ClassDoc)],
+    output: &Output,
+    format: Format,
+) -> anyhow::Result<()> {
+    let pages = output.pages_dir();
+    let index_file = format!("{INDEX}.{}", format.extension());
+    let index = pages.join(&index_file);
+    let mut packages = PackageIndex::new();
+    for (outpath, classdoc) in docs {
+        if classdoc.content.is_empty() {
+            continue;
+        }
+        let path = output.xref(&index, outpath);
+        if is_package_index(outpath) {
+            let entry = packages.entry(classdoc.package.clone()).or_default();
+            entry.overview = Some(path);
+        } else if !classdoc.types.is_empty() {
+            let entry = packages.entry(classdoc.package.clone()).or_default();
+            entry.types.extend(
+                classdoc
+                    .types
+                    .iter()
+                    .map(|name| (name.clone(), path.clone())),
+            );
+        }
+    }
+    if packages.is_empty() {
+        info!("Skipping index and navigation as nothing was documented");
+        return Ok(());
+    }
 
+    if docs.iter().any(|(outpath, _)| *outpath == index) {
+        warn!("Not writing the index to {index:?} as a package overview page is written there");
+    } else {
+        debug!("Writing index to {index:?}");
+        write(&index, render_index(&packages, format.text_format())).await?;
+    }
+    if format != Format::Asciidoc {
+        return Ok(());
+    }
+    let nav = output.nav_dir().join(NAV);
+    debug!("Writing navigation to {nav:?}");
+    write(&nav, render_nav(&packages, &index_file)).await?;
+    Ok(())
+}
+
+/// Writes a minimal Antora component descriptor, unless there already is one
+async fn write_antora_descriptor(output: &Output) -> anyhow::Result<()> {
+    let descriptor = output.outdir.join(ANTORA_YML);
+    if descriptor.exists() {
+        debug!("Keeping existing {descriptor:?}");
+        return Ok(());
+    }
+    let title = t!("index_title");
+    let module = &output.module;
+    let content =
+        format!("name: api\nversion: ~\ntitle: {title}\nnav:\n- modules/{module}/{NAV}\n");
+    debug!("Writing Antora component descriptor to {descriptor:?}");
+    write(&descriptor, content).await?;
     Ok(())
 }
@@ -1,7 +1,167 @@
 use anyhow::anyhow;
-use std::path::{Path, PathBuf};
+use clap::ValueEnum;
+use std::{
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
 
-pub fn build_output_path(file: &PathBuf, out: &str) -> anyhow::Result<PathBuf> {
+use crate::javadoc::TextFormat;
+
+/// Format of the generated pages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Asciidoc,
+    Markdown,
+    /// The documentation model, see `docs/json.md`
+    Json,
+}
+
+impl Format {
+    /// File extension of the generated pages
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Asciidoc => "adoc",
+            Format::Markdown => "md",
+            Format::Json => "json",
+        }
+    }
+
+    /// Text format the documentation comments are converted to, markdown for JSON
+    pub fn text_format(&self) -> TextFormat {
+        match self {
+            Format::Asciidoc => TextFormat::Asciidoc,
+            Format::Markdown | Format::Json => TextFormat::Markdown,
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Asciidoc => write!(f, "asciidoc"),
+            Format::Markdown => write!(f, "markdown"),
+            Format::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// How the generated pages are laid out in the output directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Layout {
+    /// Mirror the paths of the source files
+    #[default]
+    Source,
+    /// One directory per package
+    Package,
+    /// Pages of an Antora module, in one directory per package
+    Antora,
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Layout::Source => write!(f, "source"),
+            Layout::Package => write!(f, "package"),
+            Layout::Antora => write!(f, "antora"),
+        }
+    }
+}
+
+/// Where the generated pages are written to and how they refer to each other
+#[derive(Debug, Clone)]
+pub struct Output {
+    pub layout: Layout,
+    pub outdir: PathBuf,
+    /// Name of the Antora module the pages belong to
+    pub module: String,
+    /// Prefixes removed from the paths of source files before mirroring them
+    pub source_roots: Vec<PathBuf>,
+}
+
+impl Output {
+    /// Directory containing all pages
+    pub fn pages_dir(&self) -> PathBuf {
+        match self.layout {
+            Layout::Source | Layout::Package => self.outdir.clone(),
+            Layout::Antora => self.module_dir().join("pages"),
+        }
+    }
+
+    /// Directory the navigation file is written to
+    pub fn nav_dir(&self) -> PathBuf {
+        match self.layout {
+            Layout::Source | Layout::Package => self.outdir.clone(),
+            Layout::Antora => self.module_dir(),
+        }
+    }
+
+    fn module_dir(&self) -> PathBuf {
+        self.outdir.join("modules").join(&self.module)
+    }
+
+    /// Path of a source file below the first matching source root, or the path itself if it isn't
+    /// below any of them
+    fn strip_source_root(&self, file: &Path) -> PathBuf {
+        let file = without_current_dir(file);
+        self.source_roots
+            .iter()
+            .find_map(|root| file.strip_prefix(without_current_dir(root)).ok())
+            .map(|stripped| stripped.to_owned())
+            .unwrap_or(file)
+    }
+
+    /// Directory the page of the source file `file`, declared in `package`, is written to
+    pub fn page_dir(&self, file: &Path, package: Option<&str>) -> anyhow::Result<PathBuf> {
+        match self.layout {
+            Layout::Source => {
+                let file = self.strip_source_root(file);
+                build_output_path(&file, &self.outdir.to_string_lossy())
+            }
+            Layout::Package | Layout::Antora => Ok(package_dir(&self.pages_dir(), package)),
+        }
+    }
+
+    /// Directory `xref:` targets are relative to, instead of the referring page
+    pub fn xref_root(&self) -> Option<PathBuf> {
+        match self.layout {
+            Layout::Source | Layout::Package => None,
+            Layout::Antora => Some(self.pages_dir()),
+        }
+    }
+
+    /// Target of an `xref:` from the page `from` to the page `to`
+    pub fn xref(&self, from: &Path, to: &Path) -> String {
+        xref_target(from, to, self.xref_root().as_deref())
+    }
+}
+
+/// Removes all `.` components, so `./src/Foo.java` starts with `src`
+fn without_current_dir(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// Directory of a package below `root`, like `root/com/acme` for `com.acme`
+pub fn package_dir(root: &Path, package: Option<&str>) -> PathBuf {
+    match package {
+        Some(package) => root.join(package.replace('.', "/")),
+        None => root.to_owned(),
+    }
+}
+
+/// Target of an `xref:` from the page `from` to the page `to`, either relative to the directory
+/// of `from` or, as Antora resolves page references, to the directory `root`
+pub fn xref_target(from: &Path, to: &Path, root: Option<&Path>) -> String {
+    let base = match root {
+        Some(root) => root,
+        None => from.parent().unwrap_or(Path::new("")),
+    };
+    relative_path(base, to).to_string_lossy().into_owned()
+}
+
+pub fn build_output_path(file: &Path, out: &str) -> anyhow::Result<PathBuf> {
     let outdir = Path::new(out);
     if file.is_absolute() {
         // joining an absolute path into another path replaces the original completely
@@ -18,7 +178,7 @@ pub fn build_output_path(file: &PathBuf, out: &str) -> anyhow::Result<PathBuf> {
         let outdir = outdir.to_owned();
         Ok(outdir)
     } else {
-        let outdir = outdir.join(file.clone());
+        let outdir = outdir.join(file);
         let outdir = outdir
             .parent()
             .ok_or_else(|| anyhow!("Failed to get parent"))?;
@@ -28,6 +188,28 @@ pub fn build_output_path(file: &PathBuf, out: &str) -> anyhow::Result<PathBuf> {
     }
 }
 
+/// Builds the path of `file` relative to the directory `from`, e.g. for `xref:` links between generated pages
+pub fn relative_path(from: &Path, file: &Path) -> PathBuf {
+    let mut from_components = from.components().peekable();
+    let mut file_components = file.components().peekable();
+    while let (Some(a), Some(b)) = (from_components.peek(), file_components.peek()) {
+        if a != b {
+            break;
+        }
+        from_components.next();
+        file_components.next();
+    }
+
+    let mut result = PathBuf::new();
+    for _ in from_components {
+        result.push("..");
+    }
+    for component in file_components {
+        result.push(component);
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,7 +219,7 @@ mod tests {
         let input = Path::new("src/java/main/superclass.java");
         let outdir = "tmp";
 
-        let result = build_output_path(&input.to_path_buf(), outdir).unwrap();
+        let result = build_output_path(input, outdir).unwrap();
 
         assert_eq!(result.to_string_lossy(), "tmp/src/java/main");
     }
@@ -47,7 +229,7 @@ mod tests {
         let input = Path::new("/data/src/java/main/superclass.java");
         let outdir = "/tmp";
 
-        let result = build_output_path(&input.to_path_buf(), outdir).unwrap();
+        let result = build_output_path(input, outdir).unwrap();
 
         assert_eq!(result.to_string_lossy(), "/tmp/data/src/java/main");
     }
@@ -57,7 +239,7 @@ mod tests {
         let input = Path::new("src/java/main/superclass.java");
         let outdir = "tmp/out";
 
-        let result = build_output_path(&input.to_path_buf(), outdir).unwrap();
+        let result = build_output_path(input, outdir).unwrap();
 
         assert_eq!(result.to_string_lossy(), "tmp/out/src/java/main");
     }
@@ -67,7 +249,7 @@ mod tests {
         let input = Path::new("/data/src/java/main/superclass.java");
         let outdir = "/tmp/out";
 
-        let result = build_output_path(&input.to_path_buf(), outdir).unwrap();
+        let result = build_output_path(input, outdir).unwrap();
 
         assert_eq!(result.to_string_lossy(), "/tmp/out/data/src/java/main");
     }
@@ -77,7 +259,7 @@ mod tests {
         let input = Path::new("src/java/main/superclass.java");
         let outdir = "/tmp";
 
-        let result = build_output_path(&input.to_path_buf(), outdir).unwrap();
+        let result = build_output_path(input, outdir).unwrap();
 
         assert_eq!(result.to_string_lossy(), "/tmp/src/java/main");
     }
@@ -86,8 +268,86 @@ mod tests {
         let input = Path::new("/src/java/main/superclass.java");
         let outdir = "tmp";
 
-        let result = build_output_path(&input.to_path_buf(), outdir).unwrap();
+        let result = build_output_path(input, outdir).unwrap();
 
         assert_eq!(result.to_string_lossy(), "tmp/src/java/main");
     }
+
+    #[test]
+    fn antora_pages() {
+        let output = Output {
+            layout: Layout::Antora,
+            outdir: PathBuf::from("docs"),
+            module: "api".to_owned(),
+            source_roots: vec![],
+        };
+        let input = Path::new("src/main/java/com/acme/Foo.java");
+
+        let result = output.page_dir(input, Some("com.acme")).unwrap();
+
+        assert_eq!(result.to_string_lossy(), "docs/modules/api/pages/com/acme");
+        assert_eq!(output.nav_dir().to_string_lossy(), "docs/modules/api");
+        assert_eq!(
+            output.xref(
+                Path::new("docs/modules/api/pages/com/acme/Foo.adoc"),
+                Path::new("docs/modules/api/pages/com/other/Bar.adoc")
+            ),
+            "com/other/Bar.adoc"
+        );
+    }
+
+    #[test]
+    fn package_pages() {
+        let output = Output {
+            layout: Layout::Package,
+            outdir: PathBuf::from("out"),
+            module: "ROOT".to_owned(),
+            source_roots: vec![],
+        };
+        let input = Path::new("/home/ci/build/src/main/java/com/acme/Foo.java");
+
+        let result = output.page_dir(input, Some("com.acme")).unwrap();
+
+        assert_eq!(result.to_string_lossy(), "out/com/acme");
+    }
+
+    #[test]
+    fn strips_source_roots() {
+        let output = Output {
+            layout: Layout::Source,
+            outdir: PathBuf::from("out"),
+            module: "ROOT".to_owned(),
+            source_roots: vec![PathBuf::from("lib/src"), PathBuf::from("./src/main/java")],
+        };
+
+        let stripped = output
+            .page_dir(Path::new("./src/main/java/com/acme/Foo.java"), None)
+            .unwrap();
+        let unchanged = output
+            .page_dir(Path::new("test/com/acme/Foo.java"), None)
+            .unwrap();
+
+        assert_eq!(stripped.to_string_lossy(), "out/com/acme");
+        assert_eq!(unchanged.to_string_lossy(), "out/test/com/acme");
+    }
+
+    #[test]
+    fn relative_path_same_dir() {
+        let from = Path::new("tmp/com/acme");
+        let file = Path::new("tmp/com/acme/Foo.adoc");
+
+        let result = relative_path(from, file);
+
+        assert_eq!(result.to_string_lossy(), "Foo.adoc");
+    }
+
+    #[test]
+    fn relative_path_other_dir() {
+        let from = Path::new("tmp/a/com/acme");
+        let file = Path::new("tmp/b/com/acme/Foo.adoc");
+
+        let result = relative_path(from, file);
+
+        assert_eq!(result.to_string_lossy(), "../../../b/com/acme/Foo.adoc");
+    }
 }
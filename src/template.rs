@@ -0,0 +1,206 @@
+//! Rendering of pages from templates, either user supplied ones or the built-in ones of the text
+//! format. Every template not found in the template directory falls back to the built-in one of
+//! the same name. Markdown pages are always rendered from templates.
+
+use std::path::Path;
+
+use anyhow::Context;
+use minijinja::{context, value::Kwargs, AutoEscape, Environment, Value};
+use rust_i18n::t;
+use smol::fs::read_to_string;
+use tracing::debug;
+
+use crate::javadoc::{
+    model::Page,
+    prefix_hashes,
+    signature::{escape_cell, escape_markdown_cell},
+    TextFormat,
+};
+
+/// Template of a whole page, including the package and module overviews
+const PAGE: &str = "page.jinja";
+/// Template of a type, included for every top level and nested type
+const TYPE: &str = "type.jinja";
+/// Template of a member, included for every field, method, constructor, enum constant and
+/// annotation element
+const MEMBER: &str = "member.jinja";
+
+const ASCIIDOC: &[(&str, &str)] = &[
+    (PAGE, include_str!("../templates/asciidoc/page.jinja")),
+    (TYPE, include_str!("../templates/asciidoc/type.jinja")),
+    (MEMBER, include_str!("../templates/asciidoc/member.jinja")),
+];
+
+const MARKDOWN: &[(&str, &str)] = &[
+    (PAGE, include_str!("../templates/markdown/page.jinja")),
+    (TYPE, include_str!("../templates/markdown/type.jinja")),
+    (MEMBER, include_str!("../templates/markdown/member.jinja")),
+];
+
+/// The templates pages are rendered with
+#[derive(Debug)]
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+impl Templates {
+    /// Loads the built-in templates of `format`, each overridden by the file of the same name in
+    /// `dir`
+    pub async fn load(format: TextFormat, dir: Option<&Path>) -> anyhow::Result<Self> {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_keep_trailing_newline(true);
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        env.add_function("t", translate);
+        env.add_function("title", title);
+        env.add_function("prefix_hashes", prefix_hashes);
+        let built_ins = match format {
+            TextFormat::Asciidoc => {
+                env.add_filter("escape_cell", |content: &str| escape_cell(content));
+                ASCIIDOC
+            }
+            TextFormat::Markdown => {
+                env.add_filter("escape_cell", |content: &str| escape_markdown_cell(content));
+                MARKDOWN
+            }
+        };
+
+        for (name, built_in) in built_ins {
+            let path = dir.map(|dir| dir.join(name)).filter(|path| path.is_file());
+            let source = match path {
+                Some(path) => {
+                    debug!("Using template {path:?}");
+                    read_to_string(&path)
+                        .await
+                        .with_context(|| format!("Failed to read template {path:?}"))?
+                }
+                None => built_in.to_string(),
+            };
+            env.add_template_owned(*name, source)
+                .with_context(|| format!("Failed to parse template {name}"))?;
+        }
+        Ok(Self { env })
+    }
+
+    /// Renders the page of a file with its types at section level `level`
+    pub fn render_page(
+        &self,
+        page: &Page,
+        level: u8,
+        tagged_regions: bool,
+    ) -> anyhow::Result<String> {
+        let template = self.env.get_template(PAGE)?;
+        let rendered = template.render(context! {
+            page,
+            level,
+            outer_level => level.saturating_sub(1),
+            tagged_regions,
+        })?;
+        Ok(rendered)
+    }
+}
+
+/// Translates `key` to the current locale, replacing placeholders like `%{name}` by the keyword
+/// arguments of the same name
+fn translate(key: &str, arguments: Kwargs) -> Result<String, minijinja::Error> {
+    let mut translated = t!(key).to_string();
+    for name in arguments.args() {
+        let value: Value = arguments.get(name)?;
+        translated = translated.replace(&format!("%{{{name}}}"), &value.to_string());
+    }
+    Ok(translated)
+}
+
+/// Translates a headline like `t`, but without the asciidoc section markers, so templates can
+/// prefix it with markers of their own
+fn title(key: &str, arguments: Kwargs) -> Result<String, minijinja::Error> {
+    let translated = translate(key, arguments)?.replace("%{nesting}", "");
+    Ok(translated.trim_start_matches(['=', ' ']).to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        javadoc::{model::page, node_to_docable, FileContext, JavaDocableElement},
+        parser::parse_string,
+    };
+
+    use super::*;
+
+    const INPUT: &str = "
+package com.acme;
+
+/** A box. */
+public class Box {
+    /**
+     * Opens the box.
+     * @param force whether to force it
+     * @param <T> the content | type
+     * @since 1.2
+     */
+    public <T> void open(boolean force) {}
+}";
+
+    fn render(templates: &Templates) -> String {
+        let tree = parse_string(INPUT).unwrap();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let ctx = FileContext::from_str(INPUT).unwrap();
+        let children: Vec<JavaDocableElement> = root
+            .children(&mut cursor)
+            .filter_map(|node| node_to_docable(node, &ctx))
+            .collect();
+        templates
+            .render_page(&page(&children, &ctx), 0, false)
+            .unwrap()
+    }
+
+    #[test]
+    fn renders_built_in_templates() {
+        let templates = smol::block_on(Templates::load(TextFormat::Asciidoc, None)).unwrap();
+        let rendered = render(&templates);
+        assert!(rendered.contains("[[com.acme.Box]]\n= Box\n"));
+        assert!(rendered.contains("public class Box\n"));
+        assert!(rendered.contains("== Methods\n"));
+        assert!(rendered.contains("[[com.acme.Box-open-boolean-]]\n=== open(boolean)\n"));
+        assert!(rendered.contains("Opens the box.\nsince:: 1.2\n"));
+        assert!(rendered.contains("|`force`\n|`boolean`\n|whether to force it\n"));
+        assert!(rendered.contains(".Type Parameters\n"));
+        assert!(rendered.contains("|`T`\n|the content \\| type\n"));
+        assert!(rendered.contains("== Fields\n\n== Methods\n"));
+    }
+
+    #[test]
+    fn renders_markdown_templates() {
+        let templates = smol::block_on(Templates::load(TextFormat::Markdown, None)).unwrap();
+        let rendered = render(&templates);
+        assert!(rendered.contains("<a id=\"com.acme.Box\"></a>\n\n# Box\n"));
+        assert!(rendered.contains("```java\npublic class Box\n```\n"));
+        assert!(rendered.contains("\n## Methods\n"));
+        assert!(
+            rendered.contains("<a id=\"com.acme.Box-open-boolean-\"></a>\n\n### open(boolean)\n")
+        );
+        assert!(rendered.contains("- **since**: 1.2\n"));
+        assert!(rendered.contains("| `force` | `boolean` | whether to force it |\n"));
+        assert!(rendered.contains("| `<T>` | the content \\| type |\n"));
+    }
+
+    #[test]
+    fn overrides_templates() {
+        let dir = std::env::temp_dir().join("javadoc2adoc-templates-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(MEMBER),
+            "\n* {{ element.name }}: {{ element.tags|map(attribute='name')|join(', ') }}\n",
+        )
+        .unwrap();
+        let templates = smol::block_on(Templates::load(TextFormat::Asciidoc, Some(&dir))).unwrap();
+        let rendered = render(&templates);
+        assert!(rendered.contains("= Box\n"));
+        assert!(rendered.contains("* open(boolean): param, param, since\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}